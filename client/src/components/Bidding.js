@@ -2,9 +2,24 @@ import React, { useState, useCallback } from 'react';
 
 import Hand from './Hand';
 
+// The number of rounds a bid is for. A nap, wellington or blücher is for
+// every round so only another named bid can beat it.
+function bidRounds(bid, handSize, misere) {
+  if (typeof bid === 'number') {
+    return bid;
+  } else if (bid.endsWith('NT')) {
+    return parseInt(bid, 10);
+  } else if (bid === 'M') {
+    return misere || 0;
+  }
+  return handSize + 1;
+}
+
 export default function Bidding({ game, userID, users, socket }) {
   const [bidAmount, setBidAmount] = useState(0);
-  const minimumBid = game.lastBid || 0;
+  const minimumBid = game.lastBid
+    ? bidRounds(game.lastBid, game.hand.length, game.settings.misere)
+    : 0;
   const maximumBid = game.hand.length;
 
   const submitBid = useCallback(
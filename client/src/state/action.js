@@ -61,7 +61,8 @@ export function gameOver(
   playerScoreDelta,
   napoleonBet,
   combinedNapoleonScore,
-  allies
+  allies,
+  standings
 ) {
   return {
     type: 'GAME_OVER',
@@ -70,5 +71,6 @@ export function gameOver(
     napoleonBet,
     combinedNapoleonScore,
     allies,
+    standings,
   };
 }
@@ -69,6 +69,7 @@ function gameReducer(state = {}, action, rootState) {
     newState.napoleonBet = action.napoleonBet;
     newState.combinedNapoleonScore = action.combinedNapoleonScore;
     newState.allies = action.allies;
+    newState.standings = action.standings;
     return newState;
   case 'GAME_BECOME_ALLY':
     newState.ally = true;
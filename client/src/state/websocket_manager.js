@@ -40,6 +40,11 @@ export default class WebsocketManager {
       }
 
       this.store.dispatch(joinedRoom(key, host, users));
+    } else if (msg[0] === 'x') {
+      const comma = msg.indexOf(',');
+      const code = msg.slice(1, comma);
+      const message = msg.slice(comma + 1);
+      console.error(`Server rejected command (${code}): ${message}`);
     } else if (msg[0] === 'j') {
       const parts = msg.split(',');
       const username = parts[0].slice(1);
@@ -62,10 +67,13 @@ export default class WebsocketManager {
       }
       this.store.dispatch(gameReceiveHand(hand));
     } else if (msg.slice(0, 2) === 'bp') {
-      const playerBid = msg.slice(1).split(',');
+      const playerBid = msg.slice(2).split(',');
       const playerID = playerBid[0];
       if (playerBid.length === 2) {
-        const bid = parseInt(playerBid[1], 10);
+        // A number of rounds, or a named bid (N, W, B, M) or no trumps (3NT)
+        const bid = /^\d+$/.test(playerBid[1])
+          ? parseInt(playerBid[1], 10)
+          : playerBid[1];
         this.store.dispatch(gamePlayerBid(playerID, bid));
       } else {
         this.store.dispatch(gamePlayerBid(playerID));
@@ -106,7 +114,9 @@ export default class WebsocketManager {
       const winnerPlayerID = msg.slice(1);
       this.store.dispatch(gameRoundOver(winnerPlayerID));
     } else if (msg[0] === 'g') {
-      const parts = msg.slice(1).split(',');
+      // The result of the game, then everyone's total score on the second line
+      const lines = msg.slice(1).split('\n');
+      const parts = lines[0].split(',');
       const [
         napoleonScoreDelta,
         playerScoreDelta,
@@ -119,13 +129,21 @@ export default class WebsocketManager {
           allies.push(parts[i]);
         }
       }
+      const standings = {};
+      if (lines.length > 1 && lines[1] !== '') {
+        for (const standing of lines[1].split(',')) {
+          const [playerID, totalScore] = standing.split(':');
+          standings[playerID] = parseInt(totalScore, 10);
+        }
+      }
       this.store.dispatch(
         gameOver(
           napoleonScoreDelta,
           playerScoreDelta,
           napoleonBet,
           combinedNapoleonScore,
-          allies
+          allies,
+          standings
         )
       );
     } else {
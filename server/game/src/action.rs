@@ -0,0 +1,152 @@
+use crate::{
+    Bid, BiddingError, Card, ClaimError, ClaimKind, DealError, ExchangeError, Game, GameSettings,
+    PlayingError, PostBiddingError, SettingsError, Trump,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A single successful action in a game. Together with the seed and the settings, the list of
+/// actions from `Game::get_log` is a complete hand history.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    /// The cards were dealt to the players, this is always the first action
+    Deal {
+        players: usize,
+        dealer: usize,
+    },
+    Bid {
+        player_id: usize,
+        /// `None` is a pass
+        bid: Option<Bid>,
+    },
+    /// The napoleon discarded these cards after picking up the kitty
+    Discard {
+        player_id: usize,
+        cards: Vec<Card>,
+    },
+    PickAllies {
+        player_id: usize,
+        ally_cards: Vec<Card>,
+        trump: Trump,
+    },
+    PlayCard {
+        player_id: usize,
+        card: Card,
+    },
+    Claim {
+        player_id: usize,
+        kind: ClaimKind,
+    },
+    RespondToClaim {
+        player_id: usize,
+        accept: bool,
+    },
+}
+
+/// The reason that a log could not be replayed, `index` is the position of the first action that
+/// diverged.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplayError {
+    /// The log didn't start with a deal (or there was more than one deal)
+    InvalidDeal {
+        index: usize,
+    },
+    /// The settings can't be dealt for the number of players in the deal
+    Settings {
+        index: usize,
+        error: SettingsError,
+    },
+    /// The deal was for a dealer who isn't one of the players
+    Deal {
+        index: usize,
+        error: DealError,
+    },
+    Bidding {
+        index: usize,
+        error: BiddingError,
+    },
+    Exchange {
+        index: usize,
+        error: ExchangeError,
+    },
+    PostBidding {
+        index: usize,
+        error: PostBiddingError,
+    },
+    Playing {
+        index: usize,
+        error: PlayingError,
+    },
+    Claim {
+        index: usize,
+        error: ClaimError,
+    },
+}
+
+impl Game {
+    /// Rebuilds a game by dealing from the seed and then applying every action in order. Fails on
+    /// the first action that the rules don't allow.
+    pub fn replay(
+        seed: u64,
+        settings: GameSettings,
+        actions: &[Action],
+    ) -> Result<Game, ReplayError> {
+        let mut game = match actions.first() {
+            Some(Action::Deal { players, dealer }) => {
+                if let Err(error) = settings.validate(*players) {
+                    return Err(ReplayError::Settings { index: 0, error });
+                }
+                if *dealer >= *players {
+                    return Err(ReplayError::Deal {
+                        index: 0,
+                        error: DealError::InvalidDealer,
+                    });
+                }
+                Game::with_dealer(*players, settings, seed, *dealer)
+            }
+            _ => return Err(ReplayError::InvalidDeal { index: 0 }),
+        };
+
+        for (index, action) in actions.iter().enumerate().skip(1) {
+            match action.clone() {
+                Action::Deal { .. } => return Err(ReplayError::InvalidDeal { index }),
+                Action::Bid { player_id, bid } => {
+                    if let Err(error) = game.bid(player_id, bid) {
+                        return Err(ReplayError::Bidding { index, error });
+                    }
+                }
+                Action::Discard { player_id, cards } => {
+                    if let Err(error) = game.discard(player_id, cards) {
+                        return Err(ReplayError::Exchange { index, error });
+                    }
+                }
+                Action::PickAllies {
+                    player_id,
+                    ally_cards,
+                    trump,
+                } => {
+                    if let Err(error) = game.pick_allies(player_id, ally_cards, trump) {
+                        return Err(ReplayError::PostBidding { index, error });
+                    }
+                }
+                Action::PlayCard { player_id, card } => {
+                    if let Err(error) = game.play_card(player_id, card) {
+                        return Err(ReplayError::Playing { index, error });
+                    }
+                }
+                Action::Claim { player_id, kind } => {
+                    if let Err(error) = game.claim(player_id, kind) {
+                        return Err(ReplayError::Claim { index, error });
+                    }
+                }
+                Action::RespondToClaim { player_id, accept } => {
+                    if let Err(error) = game.respond_to_claim(player_id, accept) {
+                        return Err(ReplayError::Claim { index, error });
+                    }
+                }
+            }
+        }
+
+        Ok(game)
+    }
+}
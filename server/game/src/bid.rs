@@ -0,0 +1,185 @@
+use crate::{BiddingError, GameSettings};
+
+use serde::{Deserialize, Serialize};
+
+/// How a no-trump bid ranks against a bid of the same number of rounds with trumps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum NoTrumpRanking {
+    /// They rank the same, so a no-trump bid has to be for more rounds to outbid a bid with trumps
+    Equal,
+    /// A no-trump bid beats a bid of the same number of rounds with trumps
+    Higher,
+}
+
+/// A bid made during the bidding phase, the napoleon is whoever makes the highest bid.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Bid {
+    /// Win at least this many rounds (together with the allies)
+    Tricks(u32),
+    /// Win at least this many rounds with no trumps, this can only be bid if it is enabled in the
+    /// settings
+    NoTrump(u32),
+    /// Win every round
+    Nap,
+    /// A nap at double the stakes, this can only be bid directly over a nap
+    Wellington,
+    /// A nap at triple the stakes, this can only be bid directly over a wellington
+    Blucher,
+    /// Win no rounds at all (together with the allies), this can only be bid if it is enabled in
+    /// the settings
+    Misere,
+}
+
+impl Bid {
+    /// The number of rounds that the napoleon and their allies need to win.
+    pub fn tricks(&self, hand_size: u32) -> u32 {
+        match self {
+            Bid::Tricks(tricks) | Bid::NoTrump(tricks) => *tricks,
+            Bid::Nap | Bid::Wellington | Bid::Blucher => hand_size,
+            Bid::Misere => 0,
+        }
+    }
+
+    /// Whether the napoleon made their contract given the number of rounds won by them and their
+    /// allies, winning more rounds than were bid still makes the contract.
+    pub fn is_made(&self, combined_napoleon_score: u32, hand_size: u32) -> bool {
+        match self {
+            Bid::Misere => combined_napoleon_score == 0,
+            bid => combined_napoleon_score >= bid.tricks(hand_size),
+        }
+    }
+
+    /// What the score for the game is multiplied by. A nap pays double, a wellington doubles the
+    /// stakes of a nap and a blücher triples them.
+    pub fn multiplier(&self) -> u32 {
+        match self {
+            Bid::Tricks(_) | Bid::NoTrump(_) | Bid::Misere => 1,
+            Bid::Nap => 2,
+            Bid::Wellington => 4,
+            Bid::Blucher => 6,
+        }
+    }
+
+    /// Bidding every round is the same as bidding a nap so that is how it is recorded.
+    pub fn normalise(self, hand_size: u32) -> Bid {
+        match self {
+            Bid::Tricks(tricks) if tricks == hand_size => Bid::Nap,
+            bid => bid,
+        }
+    }
+
+    /// The position of the bid in the bidding order, ranks are spaced out so that a higher
+    /// ranking no-trump bid and then misère can sit in between two trick counts.
+    fn rank(&self, settings: &GameSettings) -> u32 {
+        match self {
+            Bid::Tricks(tricks) => tricks * 4,
+            Bid::NoTrump(tricks) => match settings.no_trump {
+                Some(NoTrumpRanking::Higher) => tricks * 4 + 2,
+                _ => tricks * 4,
+            },
+            Bid::Misere => {
+                let misere = settings.misere.unwrap_or(0);
+                misere.min(settings.hand_size.saturating_sub(1)) * 4 + 3
+            }
+            Bid::Nap => settings.hand_size * 4,
+            Bid::Wellington => settings.hand_size * 4 + 4,
+            Bid::Blucher => settings.hand_size * 4 + 8,
+        }
+    }
+
+    /// Whether this bid beats the current highest bid (if there is one).
+    pub fn outbids(&self, current: Option<&Bid>, settings: &GameSettings) -> bool {
+        match (self, current) {
+            (Bid::Misere, _) if settings.misere.is_none() => false,
+            (Bid::NoTrump(_), _) if settings.no_trump.is_none() => false,
+            // Every contract has to win at least one round
+            (Bid::Tricks(0), _) | (Bid::NoTrump(0), _) => false,
+            (Bid::Wellington, Some(Bid::Nap)) | (Bid::Blucher, Some(Bid::Wellington)) => true,
+            (Bid::Wellington, _) | (Bid::Blucher, _) => false,
+            (bid, None) => bid.rank(settings) >= 1,
+            (bid, Some(current)) => bid.rank(settings) > current.rank(settings),
+        }
+    }
+
+    /// Every bid that could possibly be made with the given settings, from lowest to highest.
+    pub fn all(settings: &GameSettings) -> impl Iterator<Item = Bid> {
+        let mut bids: Vec<Bid> = (1..settings.hand_size).map(Bid::Tricks).collect();
+        if settings.misere.is_some() {
+            bids.push(Bid::Misere);
+        }
+        if settings.no_trump.is_some() {
+            bids.extend((1..=settings.hand_size).map(Bid::NoTrump));
+        }
+        bids.extend(vec![Bid::Nap, Bid::Wellington, Bid::Blucher]);
+        // The sort is stable so bids of the same rank stay with the ones with trumps first
+        bids.sort_by_key(|bid| bid.rank(settings));
+
+        bids.into_iter()
+    }
+}
+
+/// Checks that `bid` can be made over the current highest bid.
+pub(crate) fn check_bid(
+    bid: &Bid,
+    current: Option<&Bid>,
+    settings: &GameSettings,
+) -> Result<(), BiddingError> {
+    if let Bid::Tricks(tricks) | Bid::NoTrump(tricks) = bid {
+        if *tricks > settings.hand_size {
+            return Err(BiddingError::BidTooHigh {
+                max: settings.hand_size,
+            });
+        }
+    }
+
+    if bid.outbids(current, settings) {
+        return Ok(());
+    }
+
+    if let Bid::Wellington | Bid::Blucher | Bid::Misere = bid {
+        return Err(BiddingError::BidNotAllowed);
+    }
+    if let (Bid::NoTrump(_), None) = (bid, &settings.no_trump) {
+        return Err(BiddingError::BidNotAllowed);
+    }
+
+    match Bid::all(settings).find(|min| min.outbids(current, settings)) {
+        Some(min) => Err(BiddingError::BidTooLow { min }),
+        None => Err(BiddingError::BidNotAllowed),
+    }
+}
+
+impl std::fmt::Display for Bid {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Bid::Tricks(tricks) => write!(fmt, "{}", tricks),
+            Bid::NoTrump(tricks) => write!(fmt, "{}NT", tricks),
+            Bid::Nap => write!(fmt, "N"),
+            Bid::Wellington => write!(fmt, "W"),
+            Bid::Blucher => write!(fmt, "B"),
+            Bid::Misere => write!(fmt, "M"),
+        }
+    }
+}
+
+/// The bid wasn't a number of rounds (optionally followed by `NT`) or one of `N`, `W`, `B` and
+/// `M`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseBidError;
+
+impl std::str::FromStr for Bid {
+    type Err = ParseBidError;
+
+    fn from_str(s: &str) -> Result<Bid, ParseBidError> {
+        Ok(match s {
+            "N" => Bid::Nap,
+            "W" => Bid::Wellington,
+            "B" => Bid::Blucher,
+            "M" => Bid::Misere,
+            _ => match s.strip_suffix("NT") {
+                Some(tricks) => Bid::NoTrump(tricks.parse().map_err(|_| ParseBidError)?),
+                None => Bid::Tricks(s.parse().map_err(|_| ParseBidError)?),
+            },
+        })
+    }
+}
@@ -0,0 +1,296 @@
+use crate::deck::{join_cards, Card, Deck, Number, ParseCardError, Suit};
+use crate::settings::{MAX_JOKERS, MAX_PACK_COUNT};
+
+use serde::{Deserialize, Serialize};
+
+const SUITS: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Spades, Suit::Clubs];
+const NUMBERS: [Number; 13] = [
+    Number::Two,
+    Number::Three,
+    Number::Four,
+    Number::Five,
+    Number::Six,
+    Number::Seven,
+    Number::Eight,
+    Number::Nine,
+    Number::Ten,
+    Number::Jack,
+    Number::Queen,
+    Number::King,
+    Number::Ace,
+];
+/// A set of cards stored as bits, with one bit per card so that membership, suit and set
+/// operations are all O(1), and the set is small enough to copy rather than allocate.
+///
+/// Every suit takes up 13 consecutive bits from two up to ace, so iterating goes through the suits
+/// one at a time in rank order. When playing with more than one pack the set can hold duplicates:
+/// `layers[0]` has the cards held at least once, `layers[1]` the cards held at least twice and so
+/// on, so each layer is always a subset of the one below it. Jokers don't belong to a suit and
+/// every pack can have several, so they are only counted and come after every other card.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CardSet {
+    layers: [u64; MAX_PACK_COUNT],
+    jokers: u8,
+}
+
+/// The most jokers that a set can hold, as many as the largest deck has.
+const MAX_SET_JOKERS: u8 = (MAX_PACK_COUNT * MAX_JOKERS) as u8;
+
+fn index(card: &Card) -> u32 {
+    let suit = SUITS
+        .iter()
+        .position(|suit| suit == &card.suit)
+        .expect("Every suit other than jokers has a position") as u32;
+    let number = NUMBERS
+        .iter()
+        .position(|number| number == &card.number)
+        .expect("Every number other than jokers has a position") as u32;
+
+    suit * 13 + number
+}
+
+fn card(index: u32) -> Card {
+    Card::new(
+        SUITS[(index / 13) as usize].clone(),
+        NUMBERS[(index % 13) as usize].clone(),
+    )
+}
+
+fn suit_mask(suit: &Suit) -> u64 {
+    match SUITS.iter().position(|s| s == suit) {
+        Some(i) => 0x1fff << (i * 13),
+        None => 0,
+    }
+}
+
+impl CardSet {
+    pub fn new() -> CardSet {
+        CardSet::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|layer| layer.count_ones() as usize)
+            .sum::<usize>()
+            + self.jokers as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers[0] == 0 && self.jokers == 0
+    }
+
+    /// Adds a copy of the card, returns false (leaving the set as it was) if the set already
+    /// holds as many copies as the largest deck has.
+    pub fn insert(&mut self, card: Card) -> bool {
+        if card.is_joker() {
+            if self.jokers == MAX_SET_JOKERS {
+                return false;
+            }
+            self.jokers += 1;
+            return true;
+        }
+
+        let bit = 1 << index(&card);
+        match self.layers.iter_mut().find(|layer| **layer & bit == 0) {
+            Some(layer) => *layer |= bit,
+            None => return false,
+        }
+
+        true
+    }
+
+    /// Removes one copy of the card, returns false if the set didn't contain it.
+    pub fn remove(&mut self, card: &Card) -> bool {
+        if card.is_joker() {
+            if self.jokers == 0 {
+                return false;
+            }
+            self.jokers -= 1;
+            return true;
+        }
+
+        let bit = 1 << index(card);
+        match self
+            .layers
+            .iter_mut()
+            .rev()
+            .find(|layer| **layer & bit != 0)
+        {
+            Some(layer) => *layer &= !bit,
+            None => return false,
+        }
+
+        true
+    }
+
+    pub fn contains(&self, card: &Card) -> bool {
+        self.count(card) > 0
+    }
+
+    /// The number of copies of the card in the set.
+    pub fn count(&self, card: &Card) -> usize {
+        if card.is_joker() {
+            return self.jokers as usize;
+        }
+
+        let bit = 1 << index(card);
+        self.layers
+            .iter()
+            .take_while(|layer| **layer & bit != 0)
+            .count()
+    }
+
+    pub fn contains_suit(&self, suit: &Suit) -> bool {
+        match suit {
+            Suit::Joker => self.jokers > 0,
+            suit => self.layers[0] & suit_mask(suit) != 0,
+        }
+    }
+
+    /// Every card in the set of the given suit.
+    pub fn suit(&self, suit: &Suit) -> CardSet {
+        let mask = suit_mask(suit);
+        let mut set = CardSet::new();
+        for (layer, own) in set.layers.iter_mut().zip(&self.layers) {
+            *layer = own & mask;
+        }
+        if suit == &Suit::Joker {
+            set.jokers = self.jokers;
+        }
+
+        set
+    }
+
+    /// Iterates over the cards one suit at a time, from lowest to highest rank within each suit,
+    /// and then the jokers. Duplicates are returned once for every copy.
+    pub fn iter(&self) -> impl Iterator<Item = Card> + '_ {
+        let mut bits = self.layers[0];
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let index = bits.trailing_zeros();
+            bits &= bits - 1;
+            Some(index)
+        })
+        .flat_map(move |index| {
+            let copies = self
+                .layers
+                .iter()
+                .take_while(|layer| **layer & (1 << index) != 0)
+                .count();
+            (0..copies).map(move |_| card(index))
+        })
+        .chain((0..self.jokers).map(|_| Card::joker()))
+    }
+
+    /// The cards in either set, a card in both is held as many times as in whichever has more.
+    pub fn union(&self, other: &CardSet) -> CardSet {
+        let mut set = *self;
+        for (layer, other) in set.layers.iter_mut().zip(&other.layers) {
+            *layer |= other;
+        }
+        set.jokers = self.jokers.max(other.jokers);
+
+        set
+    }
+
+    /// The cards in both sets, held as many times as in whichever has fewer.
+    pub fn intersection(&self, other: &CardSet) -> CardSet {
+        let mut set = *self;
+        for (layer, other) in set.layers.iter_mut().zip(&other.layers) {
+            *layer &= other;
+        }
+        set.jokers = self.jokers.min(other.jokers);
+
+        set
+    }
+
+    /// The cards in this set with one copy removed for every copy in `other`.
+    pub fn difference(&self, other: &CardSet) -> CardSet {
+        let mut set = *self;
+        for other in &other.layers {
+            // Take every card from the highest layer that it is in so that each layer stays a
+            // subset of the one below
+            let mut remaining = *other;
+            for layer in set.layers.iter_mut().rev() {
+                let removed = *layer & remaining;
+                *layer &= !removed;
+                remaining &= !removed;
+            }
+        }
+        set.jokers = self.jokers.saturating_sub(other.jokers);
+
+        set
+    }
+
+    /// Whether every card in this set (including every copy) is also in `other`.
+    pub fn is_subset(&self, other: &CardSet) -> bool {
+        self.jokers <= other.jokers
+            && self
+                .layers
+                .iter()
+                .zip(&other.layers)
+                .all(|(layer, other)| layer & !other == 0)
+    }
+}
+
+/// Copies of a card past as many as the largest deck has are left out, see `CardSet::insert`.
+impl std::iter::FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> CardSet {
+        let mut set = CardSet::new();
+        for card in cards {
+            set.insert(card);
+        }
+
+        set
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = std::vec::IntoIter<Card>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter().collect::<Vec<_>>().into_iter()
+    }
+}
+
+impl From<&Deck> for CardSet {
+    fn from(deck: &Deck) -> CardSet {
+        deck.iter().cloned().collect()
+    }
+}
+
+impl From<&CardSet> for Deck {
+    /// The deck is sorted in the same order as `CardSet::iter`.
+    fn from(set: &CardSet) -> Deck {
+        let mut deck = Deck::new_empty();
+        for card in set.iter() {
+            deck.push(card);
+        }
+
+        deck
+    }
+}
+
+impl std::fmt::Display for CardSet {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        join_cards(fmt, self.iter())
+    }
+}
+
+impl std::str::FromStr for CardSet {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<CardSet, ParseCardError> {
+        let deck = s.parse::<Deck>()?;
+        let set = CardSet::from(&deck);
+        if set.len() != deck.len() {
+            return Err(ParseCardError::TooManyCopies);
+        }
+
+        Ok(set)
+    }
+}
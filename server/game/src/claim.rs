@@ -0,0 +1,314 @@
+use crate::deck::{Card, Suit, Trump};
+use crate::{Action, CardSet, Game, GameState, PlayingEvent, RuleSet};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The most positions that `Game::check_claim` looks at before it gives up. Checking the last few
+/// rounds takes far fewer than this but a full hand at a big table can take billions.
+pub const MAX_CLAIM_CHECK_POSITIONS: usize = 50_000;
+
+/// The positions that have already been searched, with whether the side wins every round from
+/// there.
+type Searched = HashMap<Position, bool>;
+
+/// A claim that settles the rest of the game without playing it out.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ClaimKind {
+    /// The player says that they will win every remaining round
+    AllTricks,
+    /// The napoleon gives up, their side won't win any of the remaining rounds
+    Concede,
+}
+
+/// A claim waiting for the other players to accept it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Claim {
+    pub player_id: usize,
+    pub kind: ClaimKind,
+    /// The players who have accepted so far
+    pub accepted: Vec<usize>,
+}
+
+/// The rounds that were settled by an accepted claim instead of being played out, counted for
+/// the side that was given them since nobody actually won them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SettledRounds {
+    pub napoleon_side: u32,
+    pub opponents: u32,
+}
+
+pub enum ClaimEvent {
+    /// The claim was made or accepted and these players still have to accept it
+    AwaitingResponses { players: Vec<usize> },
+    /// The player rejected the claim so the game carries on from where it was
+    Rejected { player_id: usize },
+    /// Everyone accepted the claim, the remaining rounds were settled and this is the
+    /// `PlayingEvent::GameEnded` for the game
+    Settled(PlayingEvent),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClaimError {
+    /// Only the napoleon can concede
+    NotNapoleon,
+    /// There is already a claim waiting for responses
+    ClaimPending,
+    /// There isn't a claim to respond to
+    NoClaim,
+    /// The player made the claim or has already accepted it
+    AlreadyResponded,
+    /// There is no player with that id at the table
+    NotAtTable,
+    InvalidGameState,
+}
+
+impl Game {
+    /// Claims the rest of the game for the player, or concedes it for the napoleon. Nothing more
+    /// can be played until every other player has responded with `Game::respond_to_claim`.
+    pub fn claim(&mut self, player_id: usize, kind: ClaimKind) -> Result<ClaimEvent, ClaimError> {
+        let players = self.players;
+        if let GameState::Playing {
+            napoleon, claim, ..
+        } = &mut self.state
+        {
+            if player_id >= players {
+                return Err(ClaimError::NotAtTable);
+            }
+            if self.hands.iter().all(|hand| hand.is_empty()) {
+                return Err(ClaimError::InvalidGameState);
+            }
+            if claim.is_some() {
+                return Err(ClaimError::ClaimPending);
+            }
+            if kind == ClaimKind::Concede && player_id != napoleon.player_id {
+                return Err(ClaimError::NotNapoleon);
+            }
+
+            self.log.push(Action::Claim {
+                player_id,
+                kind: kind.clone(),
+            });
+            *claim = Some(Claim {
+                player_id,
+                kind,
+                accepted: Vec::new(),
+            });
+
+            Ok(ClaimEvent::AwaitingResponses {
+                players: (0..players).filter(|id| *id != player_id).collect(),
+            })
+        } else {
+            Err(ClaimError::InvalidGameState)
+        }
+    }
+
+    /// Accepts or rejects the claim that is waiting for responses. Once everyone apart from the
+    /// player who made the claim has accepted, the remaining rounds are settled and the game ends.
+    pub fn respond_to_claim(
+        &mut self,
+        player_id: usize,
+        accept: bool,
+    ) -> Result<ClaimEvent, ClaimError> {
+        let players = self.players;
+        if let GameState::Playing {
+            napoleon,
+            allies,
+            solo,
+            claim: pending,
+            played_cards,
+            ..
+        } = &mut self.state
+        {
+            if player_id >= players {
+                return Err(ClaimError::NotAtTable);
+            }
+            let claim = pending.as_mut().ok_or(ClaimError::NoClaim)?;
+            if player_id == claim.player_id || claim.accepted.contains(&player_id) {
+                return Err(ClaimError::AlreadyResponded);
+            }
+
+            self.log.push(Action::RespondToClaim { player_id, accept });
+
+            if !accept {
+                *pending = None;
+                return Ok(ClaimEvent::Rejected { player_id });
+            }
+
+            claim.accepted.push(player_id);
+            let waiting: Vec<usize> = (0..players)
+                .filter(|id| *id != claim.player_id && !claim.accepted.contains(id))
+                .collect();
+            if !waiting.is_empty() {
+                return Ok(ClaimEvent::AwaitingResponses { players: waiting });
+            }
+
+            // A round that has been started counts as one of the remaining rounds
+            let remaining = self.hands.iter().map(|hand| hand.len()).max().unwrap_or(0) as u32;
+            let napoleon_side =
+                claim.player_id == napoleon.player_id || allies.contains(&claim.player_id);
+            if claim.kind == ClaimKind::AllTricks && napoleon_side {
+                self.settled_rounds.napoleon_side += remaining;
+            } else {
+                self.settled_rounds.opponents += remaining;
+            }
+
+            let (napoleon, allies, solo) = (napoleon.clone(), allies.clone(), *solo);
+            *pending = None;
+            played_cards.clear();
+            for hand in &mut self.hands {
+                *hand = CardSet::new();
+            }
+
+            Ok(ClaimEvent::Settled(self.game_ended(napoleon, allies, solo)))
+        } else {
+            Err(ClaimError::InvalidGameState)
+        }
+    }
+
+    /// Whether the claim is certain to come true from the cards that are actually in everyone's
+    /// hands, however the other players play. A claim of every round holds if the player can win
+    /// all of them and a concession holds if the napoleon's opponents can win all of them.
+    ///
+    /// Every way of playing out the rest of the game may have to be tried, so the search gives up
+    /// and returns `None` once it has looked at `MAX_CLAIM_CHECK_POSITIONS` positions.
+    pub fn check_claim(&self, player_id: usize, kind: &ClaimKind) -> Option<bool> {
+        let (napoleon, allies, trump, current_player, played_cards, required_suit) =
+            match &self.state {
+                GameState::Playing {
+                    napoleon,
+                    allies,
+                    trump,
+                    current_player,
+                    played_cards,
+                    required_suit,
+                    ..
+                } => (
+                    napoleon.player_id,
+                    allies,
+                    trump,
+                    *current_player,
+                    played_cards,
+                    required_suit,
+                ),
+                _ => return Some(false),
+            };
+
+        let side: Vec<usize> = match kind {
+            ClaimKind::AllTricks => vec![player_id],
+            ClaimKind::Concede => (0..self.players)
+                .filter(|id| *id != napoleon && !allies.contains(id))
+                .collect(),
+        };
+
+        let mut search = Search {
+            rules: &self.settings.rules,
+            trump,
+            side,
+            budget: MAX_CLAIM_CHECK_POSITIONS,
+            searched: Searched::new(),
+        };
+        search.side_wins_remaining(Position {
+            hands: self.hands.clone(),
+            current_player,
+            played_cards: played_cards.clone(),
+            required_suit: required_suit.clone(),
+        })
+    }
+}
+
+/// The state of the rest of the game as far as the claim search cares.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Position {
+    hands: Vec<CardSet>,
+    current_player: usize,
+    played_cards: Vec<(usize, Card)>,
+    required_suit: Option<Suit>,
+}
+
+struct Search<'a> {
+    rules: &'a RuleSet,
+    trump: &'a Trump,
+    /// The players who play together to win every round
+    side: Vec<usize>,
+    /// How many more positions can be looked at before giving up
+    budget: usize,
+    searched: Searched,
+}
+
+impl Search<'_> {
+    /// Whether the side can win every remaining round from the position whatever everyone else
+    /// plays, or `None` if that couldn't be worked out within the budget.
+    fn side_wins_remaining(&mut self, position: Position) -> Option<bool> {
+        let player_id = position.current_player;
+        let hand = position.hands[player_id];
+        if hand.is_empty() {
+            return Some(true);
+        }
+
+        // The same position is reached by playing the cards in many different orders
+        if let Some(won) = self.searched.get(&position) {
+            return Some(*won);
+        }
+        self.budget = self.budget.checked_sub(1)?;
+
+        let mut cards: Vec<Card> = Vec::new();
+        for card in hand.iter() {
+            if !cards.contains(&card)
+                && self
+                    .rules
+                    .can_play(&hand, &card, &position.required_suit, self.trump)
+            {
+                cards.push(card);
+            }
+        }
+
+        let on_side = self.side.contains(&player_id);
+        let mut won = !on_side;
+        for card in cards {
+            let next = match self.play(&position, card) {
+                Some(next) => self.side_wins_remaining(next)?,
+                None => false,
+            };
+            if next == on_side {
+                won = on_side;
+                break;
+            }
+        }
+
+        self.searched.insert(position, won);
+        Some(won)
+    }
+
+    /// The position after the current player plays the card, or `None` if that finishes a round
+    /// that the side doesn't win.
+    fn play(&self, position: &Position, card: Card) -> Option<Position> {
+        let mut next = position.clone();
+        let player_id = next.current_player;
+        next.hands[player_id].remove(&card);
+        if next.required_suit.is_none() && !card.is_joker() {
+            next.required_suit = Some(card.suit.clone());
+        }
+        next.played_cards.push((player_id, card));
+
+        if next.played_cards.len() < next.hands.len() {
+            next.current_player = (player_id + 1) % next.hands.len();
+            return Some(next);
+        }
+
+        let cards: Vec<Card> = next
+            .played_cards
+            .iter()
+            .map(|(_, card)| card.clone())
+            .collect();
+        let winner = next.played_cards[self.rules.winning_card(&cards, self.trump)].0;
+        if !self.side.contains(&winner) {
+            return None;
+        }
+
+        next.played_cards.clear();
+        next.required_suit = None;
+        next.current_player = winner;
+        Some(next)
+    }
+}
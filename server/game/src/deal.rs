@@ -0,0 +1,141 @@
+use crate::deck::{Deck, ParseCardError};
+use crate::{CardSet, Game, GameSettings};
+
+/// Every player's hand (and the kitty) along with who dealt them.
+///
+/// A deal is written on one line as the dealer, a colon and then each hand in seat order
+/// separated by spaces, optionally followed by a slash and the kitty, e.g.
+/// `1:2H,AS,KC 5D,TH,9S/XX`. This is the same card notation as the protocol so a deal can be
+/// copied straight from a log into a test or a bug report.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deal {
+    pub dealer: usize,
+    pub hands: Vec<CardSet>,
+    pub kitty: Deck,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseDealError {
+    /// There was no `{dealer}:` at the start
+    MissingDealer,
+    InvalidDealer,
+    InvalidCard(ParseCardError),
+}
+
+/// Why a deal can't be played with the given settings.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DealError {
+    NoPlayers,
+    InvalidDealer,
+    IncorrectHandSize {
+        player_id: usize,
+        expected: u32,
+        received: usize,
+    },
+    IncorrectKittySize {
+        expected: u32,
+        received: usize,
+    },
+    /// The deal has cards that aren't in the deck, or more copies than the deck has
+    CardsNotInDeck,
+}
+
+impl Game {
+    /// Starts a game from the given deal rather than shuffling. The game has a seed of 0 so it
+    /// can't be replayed from its seed, use a snapshot instead.
+    pub fn from_deal(settings: GameSettings, deal: Deal) -> Result<Game, DealError> {
+        let players = deal.hands.len();
+        if players == 0 {
+            return Err(DealError::NoPlayers);
+        }
+        if deal.dealer >= players {
+            return Err(DealError::InvalidDealer);
+        }
+
+        for (player_id, hand) in deal.hands.iter().enumerate() {
+            if hand.len() != settings.hand_size as usize {
+                return Err(DealError::IncorrectHandSize {
+                    player_id,
+                    expected: settings.hand_size,
+                    received: hand.len(),
+                });
+            }
+        }
+        if deal.kitty.len() != settings.kitty_size as usize {
+            return Err(DealError::IncorrectKittySize {
+                expected: settings.kitty_size,
+                received: deal.kitty.len(),
+            });
+        }
+
+        let dealt: CardSet = deal
+            .hands
+            .iter()
+            .flat_map(|hand| hand.iter())
+            .chain(deal.kitty.iter().cloned())
+            .collect();
+        // Copies past what the largest deck has are left out of the set so they have to be
+        // counted too
+        let count = deal.hands.iter().map(|hand| hand.len()).sum::<usize>() + deal.kitty.len();
+        let deck = CardSet::from(&Deck::with_jokers(settings.pack_count, settings.jokers));
+        if dealt.len() != count || !dealt.is_subset(&deck) {
+            return Err(DealError::CardsNotInDeck);
+        }
+
+        Ok(Game::from_hands(settings, 0, deal))
+    }
+
+    /// The cards that each player is holding and the kitty, at the start of the game this is the
+    /// deal.
+    pub fn get_deal(&self) -> Deal {
+        Deal {
+            dealer: self.dealer,
+            hands: self.hands.clone(),
+            kitty: self.kitty.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for Deal {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(fmt, "{}:", self.dealer)?;
+        for (i, hand) in self.hands.iter().enumerate() {
+            if i > 0 {
+                write!(fmt, " ")?;
+            }
+            write!(fmt, "{}", hand)?;
+        }
+        if !self.kitty.is_empty() {
+            write!(fmt, "/{}", self.kitty)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Deal {
+    type Err = ParseDealError;
+
+    fn from_str(s: &str) -> Result<Deal, ParseDealError> {
+        let (dealer, rest) = match s.find(':') {
+            Some(i) => (&s[..i], &s[i + 1..]),
+            None => return Err(ParseDealError::MissingDealer),
+        };
+        let dealer = dealer.parse().map_err(|_| ParseDealError::InvalidDealer)?;
+
+        let (hands, kitty) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i + 1..]),
+            None => (rest, ""),
+        };
+
+        Ok(Deal {
+            dealer,
+            hands: hands
+                .split(' ')
+                .map(|hand| hand.parse())
+                .collect::<Result<_, _>>()
+                .map_err(ParseDealError::InvalidCard)?,
+            kitty: kitty.parse().map_err(ParseDealError::InvalidCard)?,
+        })
+    }
+}
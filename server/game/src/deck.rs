@@ -1,9 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Why a card (or a suit, number or deck) couldn't be parsed. Cards are written as their number
+/// followed by their suit, e.g. `TH` for the ten of hearts, and decks as cards separated by commas.
 #[derive(Clone, Debug, PartialEq)]
+pub enum ParseCardError {
+    InvalidSuit(char),
+    InvalidNumber(char),
+    /// A card must be exactly two characters and a suit or number exactly one
+    InvalidLength,
+    /// Only one of the number and the suit was a joker, jokers are written as `XX`
+    InvalidJoker,
+    /// There were more copies of a card than the largest deck has
+    TooManyCopies,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Suit {
     Hearts,
     Diamonds,
     Spades,
     Clubs,
+    /// Jokers don't belong to any of the four suits so they are given a suit of their own, this
+    /// can't be picked as the trump suit
+    Joker,
 }
 
 impl Suit {
@@ -14,10 +33,11 @@ impl Suit {
             Diamonds => 'D',
             Spades => 'S',
             Clubs => 'C',
+            Joker => 'X',
         }
     }
 
-    pub fn from_char(c: char) -> Result<Suit, ()> {
+    pub fn from_char(c: char) -> Result<Suit, ParseCardError> {
         use Suit::*;
 
         Ok(match c {
@@ -25,12 +45,37 @@ impl Suit {
             'D' => Diamonds,
             'S' => Spades,
             'C' => Clubs,
-            _ => return Err(()),
+            'X' => Joker,
+            _ => return Err(ParseCardError::InvalidSuit(c)),
         })
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+/// What the napoleon picks after bidding: a trump suit, or no trumps at all in which case the
+/// highest card of the led suit wins. Written as the suit's letter or `N` for no trumps.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Trump {
+    Suit(Suit),
+    NoTrump,
+}
+
+impl Trump {
+    /// The trump suit, if there is one.
+    pub fn suit(&self) -> Option<&Suit> {
+        match self {
+            Trump::Suit(suit) => Some(suit),
+            Trump::NoTrump => None,
+        }
+    }
+}
+
+impl From<Suit> for Trump {
+    fn from(suit: Suit) -> Trump {
+        Trump::Suit(suit)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Number {
     Two,
     Three,
@@ -45,6 +90,7 @@ pub enum Number {
     Queen,
     King,
     Ace,
+    Joker,
 }
 
 impl From<&Number> for u8 {
@@ -65,12 +111,13 @@ impl From<&Number> for u8 {
             Queen => 12,
             King => 13,
             Ace => 14,
+            Joker => 15,
         }
     }
 }
 
 impl Number {
-    pub fn from_char(c: char) -> Result<Number, ()> {
+    pub fn from_char(c: char) -> Result<Number, ParseCardError> {
         use Number::*;
 
         Ok(match c {
@@ -87,12 +134,13 @@ impl Number {
             'Q' => Queen,
             'K' => King,
             'A' => Ace,
-            _ => return Err(()),
+            'X' => Joker,
+            _ => return Err(ParseCardError::InvalidNumber(c)),
         })
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Card {
     pub suit: Suit,
     pub number: Number,
@@ -103,15 +151,29 @@ impl Card {
         Card { suit, number }
     }
 
-    pub fn from_chars(s: char, n: char) -> Result<Card, ()> {
+    /// A joker, written as `XX`.
+    pub fn joker() -> Card {
+        Card::new(Suit::Joker, Number::Joker)
+    }
+
+    pub fn is_joker(&self) -> bool {
+        self.suit == Suit::Joker || self.number == Number::Joker
+    }
+
+    pub fn from_chars(s: char, n: char) -> Result<Card, ParseCardError> {
         let suit = Suit::from_char(s)?;
         let number = Number::from_char(n)?;
 
+        // A joker has to be written as `XX`
+        if (suit == Suit::Joker) != (number == Number::Joker) {
+            return Err(ParseCardError::InvalidJoker);
+        }
+
         Ok(Card { suit, number })
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Deck {
     inner: Vec<Card>,
 }
@@ -127,6 +189,17 @@ impl Deck {
         deck
     }
 
+    /// Like `new` but with this many jokers added to each pack.
+    pub fn with_jokers(pack_count: usize, jokers: usize) -> Deck {
+        let mut deck = Self::new(pack_count);
+
+        for _ in 0..pack_count * jokers {
+            deck.push(Card::joker());
+        }
+
+        deck
+    }
+
     pub fn new_full() -> Deck {
         use Number::*;
         use Suit::*;
@@ -148,6 +221,10 @@ impl Deck {
         self.inner.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
     pub fn new_empty() -> Deck {
         Deck { inner: Vec::new() }
     }
@@ -157,9 +234,14 @@ impl Deck {
     }
 
     pub fn shuffle(&mut self) {
-        use rand::prelude::*;
-        let mut rng = thread_rng();
-        self.inner.shuffle(&mut rng);
+        self.shuffle_with(&mut rand::thread_rng());
+    }
+
+    /// Shuffles the deck using the given source of randomness, for a given RNG state the resulting
+    /// order is always the same.
+    pub fn shuffle_with<R: rand::Rng + ?Sized>(&mut self, rng: &mut R) {
+        use rand::seq::SliceRandom;
+        self.inner.shuffle(rng);
     }
 
     pub fn push(&mut self, card: Card) {
@@ -174,15 +256,21 @@ impl Deck {
         self.inner.contains(card)
     }
 
+    #[allow(clippy::search_is_some)]
     pub fn contains_suit(&self, suit: &Suit) -> bool {
         self.inner.iter().find(|card| &card.suit == suit).is_some()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Card> {
+        self.inner.iter()
+    }
+
     pub fn remove(&mut self, card: &Card) -> Option<Card> {
         let index = self.inner.iter().position(|c| c == card)?;
         Some(self.inner.remove(index))
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn into_iter(self) -> impl Iterator<Item = Card> {
         self.inner.into_iter()
     }
@@ -206,6 +294,113 @@ impl std::fmt::Display for Number {
             Queen => write!(fmt, "Q"),
             King => write!(fmt, "K"),
             Ace => write!(fmt, "A"),
+            Joker => write!(fmt, "X"),
+        }
+    }
+}
+
+impl std::fmt::Display for Suit {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(fmt, "{}", self.to_char())
+    }
+}
+
+impl std::fmt::Display for Trump {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Trump::Suit(suit) => write!(fmt, "{}", suit),
+            Trump::NoTrump => write!(fmt, "N"),
+        }
+    }
+}
+
+impl std::fmt::Display for Card {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        write!(fmt, "{}{}", self.number, self.suit)
+    }
+}
+
+impl std::fmt::Display for Deck {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        join_cards(fmt, self.iter())
+    }
+}
+
+/// Writes the cards separated by commas, the notation used for both decks and card sets.
+pub(crate) fn join_cards<C: std::fmt::Display>(
+    fmt: &mut std::fmt::Formatter,
+    cards: impl Iterator<Item = C>,
+) -> Result<(), std::fmt::Error> {
+    for (i, card) in cards.enumerate() {
+        if i > 0 {
+            write!(fmt, ",")?;
+        }
+        write!(fmt, "{}", card)?;
+    }
+
+    Ok(())
+}
+
+/// Gets the only character in `s`.
+fn single_char(s: &str) -> Result<char, ParseCardError> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(ParseCardError::InvalidLength),
+    }
+}
+
+impl std::str::FromStr for Suit {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Suit, ParseCardError> {
+        Suit::from_char(single_char(s)?)
+    }
+}
+
+impl std::str::FromStr for Trump {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Trump, ParseCardError> {
+        match single_char(s)? {
+            'N' => Ok(Trump::NoTrump),
+            c => Ok(Trump::Suit(Suit::from_char(c)?)),
         }
     }
 }
+
+impl std::str::FromStr for Number {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Number, ParseCardError> {
+        Number::from_char(single_char(s)?)
+    }
+}
+
+impl std::str::FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Card, ParseCardError> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(n), Some(s), None) => Card::from_chars(s, n),
+            _ => Err(ParseCardError::InvalidLength),
+        }
+    }
+}
+
+impl std::str::FromStr for Deck {
+    type Err = ParseCardError;
+
+    /// Parses cards separated by commas, an empty string is an empty deck.
+    fn from_str(s: &str) -> Result<Deck, ParseCardError> {
+        let mut deck = Deck::new_empty();
+        if !s.is_empty() {
+            for card in s.split(',') {
+                deck.push(card.parse()?);
+            }
+        }
+
+        Ok(deck)
+    }
+}
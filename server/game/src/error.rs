@@ -0,0 +1,519 @@
+use crate::deck::ParseCardError;
+use crate::{
+    BiddingError, ClaimError, DealError, ExchangeError, ParseBidError, ParseDealError,
+    PlayingError, PostBiddingError, ReplayError, SettingsError, SnapshotError,
+};
+
+use std::fmt;
+
+/// Any error from the game crate.
+///
+/// Every error has a human readable message (its `Display`) and a `code` which never changes
+/// between versions, so tools and clients can match on the code rather than the message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Bidding(BiddingError),
+    Exchange(ExchangeError),
+    PostBidding(PostBiddingError),
+    Playing(PlayingError),
+    Claim(ClaimError),
+    Replay(ReplayError),
+    Snapshot(SnapshotError),
+    Deal(DealError),
+    ParseCard(ParseCardError),
+    ParseBid(ParseBidError),
+    ParseDeal(ParseDealError),
+    Settings(SettingsError),
+}
+
+impl Error {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Bidding(error) => error.code(),
+            Error::Exchange(error) => error.code(),
+            Error::PostBidding(error) => error.code(),
+            Error::Playing(error) => error.code(),
+            Error::Claim(error) => error.code(),
+            Error::Replay(error) => error.code(),
+            Error::Snapshot(error) => error.code(),
+            Error::Deal(error) => error.code(),
+            Error::ParseCard(error) => error.code(),
+            Error::ParseBid(error) => error.code(),
+            Error::ParseDeal(error) => error.code(),
+            Error::Settings(error) => error.code(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Bidding(error) => error.fmt(fmt),
+            Error::Exchange(error) => error.fmt(fmt),
+            Error::PostBidding(error) => error.fmt(fmt),
+            Error::Playing(error) => error.fmt(fmt),
+            Error::Claim(error) => error.fmt(fmt),
+            Error::Replay(error) => error.fmt(fmt),
+            Error::Snapshot(error) => error.fmt(fmt),
+            Error::Deal(error) => error.fmt(fmt),
+            Error::ParseCard(error) => error.fmt(fmt),
+            Error::ParseBid(error) => error.fmt(fmt),
+            Error::ParseDeal(error) => error.fmt(fmt),
+            Error::Settings(error) => error.fmt(fmt),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Bidding(error) => Some(error),
+            Error::Exchange(error) => Some(error),
+            Error::PostBidding(error) => Some(error),
+            Error::Playing(error) => Some(error),
+            Error::Claim(error) => Some(error),
+            Error::Replay(error) => Some(error),
+            Error::Snapshot(error) => Some(error),
+            Error::Deal(error) => Some(error),
+            Error::ParseCard(error) => Some(error),
+            Error::ParseBid(error) => Some(error),
+            Error::ParseDeal(error) => Some(error),
+            Error::Settings(error) => Some(error),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($($variant:ident($error:ty)),*) => {
+        $(
+            impl From<$error> for Error {
+                fn from(error: $error) -> Error {
+                    Error::$variant(error)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    Bidding(BiddingError),
+    Exchange(ExchangeError),
+    PostBidding(PostBiddingError),
+    Playing(PlayingError),
+    Claim(ClaimError),
+    Replay(ReplayError),
+    Snapshot(SnapshotError),
+    Deal(DealError),
+    ParseCard(ParseCardError),
+    ParseBid(ParseBidError),
+    ParseDeal(ParseDealError),
+    Settings(SettingsError)
+);
+
+impl BiddingError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            BiddingError::BidTooLow { .. } => "bid_too_low",
+            BiddingError::BidTooHigh { .. } => "bid_too_high",
+            BiddingError::BidNotAllowed => "bid_not_allowed",
+            BiddingError::NotCurrentPlayer { .. } => "not_current_player",
+            BiddingError::InvalidGameState => "invalid_game_state",
+            BiddingError::NoBids => "no_bids",
+        }
+    }
+}
+
+impl fmt::Display for BiddingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BiddingError::BidTooLow { min } => write!(fmt, "the lowest bid allowed is {}", min),
+            BiddingError::BidTooHigh { max } => write!(fmt, "a bid can't be more than {}", max),
+            BiddingError::BidNotAllowed => write!(fmt, "that bid isn't allowed now"),
+            BiddingError::NotCurrentPlayer { .. } => write!(fmt, "it isn't your turn to bid"),
+            BiddingError::InvalidGameState => write!(fmt, "bidding has finished"),
+            BiddingError::NoBids => write!(fmt, "everyone passed so the cards will be redealt"),
+        }
+    }
+}
+
+impl std::error::Error for BiddingError {}
+
+impl ExchangeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExchangeError::NotCurrentPlayer { .. } => "not_current_player",
+            ExchangeError::IncorrectDiscardCount { .. } => "incorrect_discard_count",
+            ExchangeError::CardNotInHand => "card_not_in_hand",
+            ExchangeError::InvalidGameState => "invalid_game_state",
+        }
+    }
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExchangeError::NotCurrentPlayer { .. } => {
+                write!(fmt, "only the napoleon can discard")
+            }
+            ExchangeError::IncorrectDiscardCount { expected, received } => write!(
+                fmt,
+                "{} cards must be discarded but {} were given",
+                expected, received
+            ),
+            ExchangeError::CardNotInHand => write!(fmt, "that card isn't in your hand"),
+            ExchangeError::InvalidGameState => write!(fmt, "cards can't be discarded now"),
+        }
+    }
+}
+
+impl std::error::Error for ExchangeError {}
+
+impl PostBiddingError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PostBiddingError::NotCurrentPlayer { .. } => "not_current_player",
+            PostBiddingError::IncorrectAllyCount { .. } => "incorrect_ally_count",
+            PostBiddingError::DuplicateAllyCard => "duplicate_ally_card",
+            PostBiddingError::InvalidTrumpSuit => "invalid_trump_suit",
+            PostBiddingError::NoTrumpBid => "no_trump_bid",
+            PostBiddingError::NoTrumpNotBid => "no_trump_not_bid",
+            PostBiddingError::CalledOwnCard => "called_own_card",
+            PostBiddingError::InvalidGameState => "invalid_game_state",
+        }
+    }
+}
+
+impl fmt::Display for PostBiddingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PostBiddingError::NotCurrentPlayer { .. } => {
+                write!(fmt, "only the napoleon can pick allies")
+            }
+            PostBiddingError::IncorrectAllyCount { expected, received } => write!(
+                fmt,
+                "{} ally cards must be called but {} were given",
+                expected, received
+            ),
+            PostBiddingError::DuplicateAllyCard => {
+                write!(fmt, "the same ally card can't be called twice")
+            }
+            PostBiddingError::InvalidTrumpSuit => write!(fmt, "jokers can't be trumps"),
+            PostBiddingError::NoTrumpBid => {
+                write!(fmt, "a no-trump contract has to be played with no trumps")
+            }
+            PostBiddingError::NoTrumpNotBid => {
+                write!(fmt, "only a no-trump contract can be played with no trumps")
+            }
+            PostBiddingError::CalledOwnCard => {
+                write!(fmt, "you can't call a card that is in your own hand")
+            }
+            PostBiddingError::InvalidGameState => write!(fmt, "allies can't be picked now"),
+        }
+    }
+}
+
+impl std::error::Error for PostBiddingError {}
+
+impl PlayingError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            PlayingError::NotCurrentPlayer { .. } => "not_current_player",
+            PlayingError::InvalidGameState => "invalid_game_state",
+            PlayingError::InvalidSuit => "invalid_suit",
+            PlayingError::CardNotInHand => "card_not_in_hand",
+            PlayingError::ClaimPending => "claim_pending",
+        }
+    }
+}
+
+impl fmt::Display for PlayingError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayingError::NotCurrentPlayer { .. } => write!(fmt, "it isn't your turn to play"),
+            PlayingError::InvalidGameState => write!(fmt, "cards can't be played now"),
+            PlayingError::InvalidSuit => write!(fmt, "that card can't be played in this round"),
+            PlayingError::CardNotInHand => write!(fmt, "that card isn't in your hand"),
+            PlayingError::ClaimPending => {
+                write!(
+                    fmt,
+                    "everyone has to respond to the claim before playing on"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlayingError {}
+
+impl ClaimError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ClaimError::NotNapoleon => "not_napoleon",
+            ClaimError::ClaimPending => "claim_pending",
+            ClaimError::NoClaim => "no_claim",
+            ClaimError::AlreadyResponded => "already_responded",
+            ClaimError::NotAtTable => "not_at_table",
+            ClaimError::InvalidGameState => "invalid_game_state",
+        }
+    }
+}
+
+impl fmt::Display for ClaimError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClaimError::NotNapoleon => write!(fmt, "only the napoleon can concede"),
+            ClaimError::ClaimPending => write!(fmt, "there is already a claim to respond to"),
+            ClaimError::NoClaim => write!(fmt, "there isn't a claim to respond to"),
+            ClaimError::AlreadyResponded => write!(fmt, "you have already responded to the claim"),
+            ClaimError::NotAtTable => write!(fmt, "there is no such player at the table"),
+            ClaimError::InvalidGameState => {
+                write!(fmt, "the rest of the game can't be claimed now")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClaimError {}
+
+impl ReplayError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReplayError::InvalidDeal { .. } => "invalid_deal",
+            ReplayError::Settings { error, .. } => error.code(),
+            ReplayError::Deal { error, .. } => error.code(),
+            ReplayError::Bidding { error, .. } => error.code(),
+            ReplayError::Exchange { error, .. } => error.code(),
+            ReplayError::PostBidding { error, .. } => error.code(),
+            ReplayError::Playing { error, .. } => error.code(),
+            ReplayError::Claim { error, .. } => error.code(),
+        }
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::InvalidDeal { index } => {
+                write!(fmt, "action {} should be the only deal", index)
+            }
+            ReplayError::Settings { index, error } => write!(fmt, "action {}: {}", index, error),
+            ReplayError::Deal { index, error } => write!(fmt, "action {}: {}", index, error),
+            ReplayError::Bidding { index, error } => write!(fmt, "action {}: {}", index, error),
+            ReplayError::Exchange { index, error } => write!(fmt, "action {}: {}", index, error),
+            ReplayError::PostBidding { index, error } => write!(fmt, "action {}: {}", index, error),
+            ReplayError::Playing { index, error } => write!(fmt, "action {}: {}", index, error),
+            ReplayError::Claim { index, error } => write!(fmt, "action {}: {}", index, error),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::InvalidDeal { .. } => None,
+            ReplayError::Settings { error, .. } => Some(error),
+            ReplayError::Deal { error, .. } => Some(error),
+            ReplayError::Bidding { error, .. } => Some(error),
+            ReplayError::Exchange { error, .. } => Some(error),
+            ReplayError::PostBidding { error, .. } => Some(error),
+            ReplayError::Playing { error, .. } => Some(error),
+            ReplayError::Claim { error, .. } => Some(error),
+        }
+    }
+}
+
+impl SnapshotError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SnapshotError::UnsupportedVersion { .. } => "unsupported_snapshot_version",
+        }
+    }
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::UnsupportedVersion { version, supported } => write!(
+                fmt,
+                "snapshot version {} isn't supported, only version {} is",
+                version, supported
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl DealError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            DealError::NoPlayers => "no_players",
+            DealError::InvalidDealer => "invalid_dealer",
+            DealError::IncorrectHandSize { .. } => "incorrect_hand_size",
+            DealError::IncorrectKittySize { .. } => "incorrect_kitty_size",
+            DealError::CardsNotInDeck => "cards_not_in_deck",
+        }
+    }
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DealError::NoPlayers => write!(fmt, "the deal has no hands"),
+            DealError::InvalidDealer => write!(fmt, "the dealer isn't one of the players"),
+            DealError::IncorrectHandSize {
+                player_id,
+                expected,
+                received,
+            } => write!(
+                fmt,
+                "player {} has {} cards instead of {}",
+                player_id, received, expected
+            ),
+            DealError::IncorrectKittySize { expected, received } => write!(
+                fmt,
+                "the kitty has {} cards instead of {}",
+                received, expected
+            ),
+            DealError::CardsNotInDeck => write!(fmt, "the deal has cards that aren't in the deck"),
+        }
+    }
+}
+
+impl std::error::Error for DealError {}
+
+impl ParseCardError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseCardError::InvalidSuit(_) => "invalid_suit_char",
+            ParseCardError::InvalidNumber(_) => "invalid_number_char",
+            ParseCardError::InvalidLength => "invalid_card_length",
+            ParseCardError::InvalidJoker => "invalid_joker",
+            ParseCardError::TooManyCopies => "too_many_copies",
+        }
+    }
+}
+
+impl std::fmt::Display for ParseCardError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::InvalidSuit(c) => write!(fmt, "'{}' is not a suit", c),
+            ParseCardError::InvalidNumber(c) => write!(fmt, "'{}' is not a card number", c),
+            ParseCardError::InvalidLength => write!(fmt, "a card is a number followed by a suit"),
+            ParseCardError::InvalidJoker => write!(fmt, "a joker is written as XX"),
+            ParseCardError::TooManyCopies => {
+                write!(fmt, "there are more copies of a card than any deck has")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl ParseBidError {
+    pub fn code(&self) -> &'static str {
+        "invalid_bid"
+    }
+}
+
+impl fmt::Display for ParseBidError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "a bid is a number of rounds (followed by NT for no trumps) or one of N, W, B and M"
+        )
+    }
+}
+
+impl std::error::Error for ParseBidError {}
+
+impl ParseDealError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseDealError::MissingDealer => "missing_dealer",
+            ParseDealError::InvalidDealer => "invalid_dealer",
+            ParseDealError::InvalidCard(error) => error.code(),
+        }
+    }
+}
+
+impl fmt::Display for ParseDealError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDealError::MissingDealer => write!(fmt, "a deal must start with the dealer"),
+            ParseDealError::InvalidDealer => write!(fmt, "the dealer isn't a number"),
+            ParseDealError::InvalidCard(error) => error.fmt(fmt),
+        }
+    }
+}
+
+impl std::error::Error for ParseDealError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseDealError::InvalidCard(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl SettingsError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SettingsError::TooFewPlayers { .. } => "too_few_players",
+            SettingsError::EmptyHands => "empty_hands",
+            SettingsError::NotEnoughCards { .. } => "not_enough_cards",
+            SettingsError::TooManyAllies { .. } => "too_many_allies",
+            SettingsError::InvalidTeams => "invalid_teams",
+            SettingsError::TooManyPacks { .. } => "too_many_packs",
+            SettingsError::TooManyJokers { .. } => "too_many_jokers",
+            SettingsError::TooManyCards => "too_many_cards",
+            SettingsError::InvalidMisere { .. } => "invalid_misere",
+            SettingsError::SoloBonusTooLarge { .. } => "solo_bonus_too_large",
+        }
+    }
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SettingsError::TooFewPlayers { min } => {
+                write!(fmt, "at least {} players are needed", min)
+            }
+            SettingsError::EmptyHands => write!(fmt, "every player must be dealt at least 1 card"),
+            SettingsError::NotEnoughCards { needed, available } => write!(
+                fmt,
+                "dealing needs {} cards but the deck only has {}",
+                needed, available
+            ),
+            SettingsError::TooManyAllies { max } => {
+                write!(fmt, "there can be at most {} allies", max)
+            }
+            SettingsError::InvalidTeams => {
+                write!(fmt, "every player needs a team and there must be two teams")
+            }
+            SettingsError::TooManyPacks { max } => {
+                write!(fmt, "there can be at most {} packs", max)
+            }
+            SettingsError::TooManyJokers { max } => {
+                write!(fmt, "there can be at most {} jokers in each pack", max)
+            }
+            SettingsError::TooManyCards => write!(fmt, "too many cards are needed to deal"),
+            SettingsError::InvalidMisere { max } => {
+                write!(
+                    fmt,
+                    "a misère can rank above a bid of at most {} rounds",
+                    max
+                )
+            }
+            SettingsError::SoloBonusTooLarge { max } => {
+                write!(
+                    fmt,
+                    "the solo bonus can be at most {} points either way",
+                    max
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
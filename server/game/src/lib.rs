@@ -1,19 +1,56 @@
+pub mod action;
+pub mod bid;
+pub mod card_set;
+pub mod claim;
+pub mod deal;
 pub mod deck;
-
-pub use deck::{Card, Deck, Suit};
-
-use serde::Serialize;
-
-#[derive(Clone)]
+pub mod error;
+pub mod rules;
+pub mod scoring;
+pub mod settings;
+pub mod snapshot;
+pub mod trick;
+pub mod view;
+
+pub use action::{Action, ReplayError};
+pub use bid::{Bid, NoTrumpRanking, ParseBidError};
+pub use card_set::CardSet;
+pub use claim::{
+    Claim, ClaimError, ClaimEvent, ClaimKind, SettledRounds, MAX_CLAIM_CHECK_POSITIONS,
+};
+pub use deal::{Deal, DealError, ParseDealError};
+pub use deck::{Card, Deck, ParseCardError, Suit, Trump};
+pub use error::Error;
+pub use rules::RuleSet;
+pub use scoring::{Score, Scoring, ScoringScheme};
+pub use settings::{
+    GameSettingsBuilder, SettingsError, MAX_JOKERS, MAX_PACK_COUNT, MAX_SOLO_BONUS,
+};
+pub use snapshot::{Snapshot, SnapshotError, SNAPSHOT_VERSION};
+pub use trick::Trick;
+pub use view::PlayerView;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Napoleon {
-    pub bid: u32,
+    pub bid: Bid,
     pub player_id: usize,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum GameState {
     Bidding {
         current_player: usize,
         current_napoleon: Option<Napoleon>,
+        /// Players who have passed, in auction mode they can no longer bid
+        passed: Vec<usize>,
+    },
+    /// The napoleon has picked up the kitty and must discard the same number of cards
+    Exchanging {
+        napoleon: Napoleon,
     },
     PostBidding {
         napoleon: Napoleon,
@@ -21,98 +58,306 @@ enum GameState {
     Playing {
         napoleon: Napoleon,
         allies: Vec<usize>,
-        trump_suit: Suit,
+        ally_cards: Vec<Card>,
+        /// Allies whose identity has become public by playing one of the ally cards
+        revealed_allies: Vec<usize>,
+        /// Nobody else holds any of the called cards so the napoleon is playing on their own
+        solo: bool,
+        /// A claim that is waiting for the other players to accept it, no cards can be played
+        /// until it has been accepted or rejected
+        claim: Option<Claim>,
+        trump: Trump,
         current_player: usize,
-        played_cards: Vec<Card>,
+        /// The cards played so far in the current round along with who played them
+        played_cards: Vec<(usize, Card)>,
         required_suit: Option<Suit>,
     },
 }
 
 pub enum BiddingEvent {
-    NextBidder { player_id: usize },
-    BiddingFinished { napoleon: Napoleon },
+    NextBidder {
+        player_id: usize,
+    },
+    BiddingFinished {
+        napoleon: Napoleon,
+        /// The cards that the napoleon picked up and must now discard (empty if there is no kitty)
+        kitty: Deck,
+    },
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum BiddingError {
-    BidTooLow { min: u32 },
-    BidTooHigh { max: u32 },
-    NotCurrentPlayer { current_player: usize },
+    BidTooLow {
+        min: Bid,
+    },
+    BidTooHigh {
+        max: u32,
+    },
+    /// A wellington or blücher was bid when it wasn't directly over a nap or wellington
+    /// respectively, or nothing can beat the current bid
+    BidNotAllowed,
+    NotCurrentPlayer {
+        current_player: usize,
+    },
     InvalidGameState,
     NoBids,
 }
 
+pub enum ExchangeEvent {
+    KittyDiscarded,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExchangeError {
+    NotCurrentPlayer { current_player: usize },
+    IncorrectDiscardCount { expected: usize, received: usize },
+    CardNotInHand,
+    InvalidGameState,
+}
+
 pub enum PostBiddingEvent {
-    AlliesChosen { allies: Vec<usize> },
+    AlliesChosen {
+        allies: Vec<usize>,
+        /// Every called card is either in the napoleon's hand or wasn't dealt to anyone (including
+        /// cards in the kitty), so the napoleon is going solo against everyone else
+        solo: bool,
+    },
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum PostBiddingError {
-    NotCurrentPlayer { current_player: usize },
-    IncorrectAllyCount { expected: usize, received: usize },
+    NotCurrentPlayer {
+        current_player: usize,
+    },
+    IncorrectAllyCount {
+        expected: usize,
+        received: usize,
+    },
+    /// The same card was called twice
+    DuplicateAllyCard,
+    /// Jokers can't be trumps
+    InvalidTrumpSuit,
+    /// The napoleon bid a no-trump contract so they can't pick a trump suit
+    NoTrumpBid,
+    /// Only a no-trump contract can be played with no trumps
+    NoTrumpNotBid,
+    /// The napoleon called a card in their own hand when `allow_self_call` is off
+    CalledOwnCard,
     InvalidGameState,
 }
 
 pub enum PlayingEvent {
+    /// An ally played one of the called cards so everyone now knows that they are an ally, this
+    /// comes before the other event for the card
+    AllyRevealed { player_id: usize, card: Card },
     NextPlayer {
         player_id: usize,
-        required_suit: Suit,
+        /// `None` if any card can be played, e.g. after a joker was led
+        required_suit: Option<Suit>,
     },
     RoundEnded {
         winner: usize,
         next_player: usize,
+        trick: Trick,
     },
     GameEnded {
         combined_napoleon_score: u32,
         napoleon: Napoleon,
         allies: Vec<usize>,
+        /// Whether the napoleon and their allies made the contract that the napoleon bid
+        contract_made: bool,
+        /// Whether the napoleon went solo, see `PostBiddingEvent::AlliesChosen`
+        solo: bool,
+        /// The change in score from the settings' scoring scheme
+        score: Score,
     },
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum PlayingError {
-    NotCurrentPlayer { current_player: usize },
+    NotCurrentPlayer {
+        current_player: usize,
+    },
     InvalidGameState,
     InvalidSuit,
     CardNotInHand,
+    /// A claim has to be accepted or rejected before play carries on
+    ClaimPending,
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Game {
     players: usize,
-    hands: Vec<Deck>,
+    hands: Vec<CardSet>,
+    /// The cards left over after dealing that the napoleon picks up (empty if there is no kitty)
+    kitty: Deck,
     score: Vec<u32>,
+    /// The rounds that weren't played because a claim settled them
+    #[serde(default)]
+    settled_rounds: SettledRounds,
+    /// Every successful bid (or pass) in the order they were made
+    bids: Vec<(usize, Option<Bid>)>,
+    /// Every round that has been completed so far
+    tricks: Vec<Trick>,
+    /// Every successful action taken in the game, enough to replay it from the seed
+    log: Vec<Action>,
     state: GameState,
     settings: GameSettings,
+    seed: u64,
+    dealer: usize,
 }
 
-#[derive(Clone, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GameSettings {
     pub ally_count: usize,
     pub hand_size: u32,
+    #[serde(default)]
+    pub rules: RuleSet,
+    /// If set then misère can be bid, ranking just above a bid of this many rounds
+    #[serde(default)]
+    pub misere: Option<u32>,
+    /// Rather than going around the table once, bidding carries on until everyone except the
+    /// highest bidder has passed (and players who pass can't bid again)
+    #[serde(default)]
+    pub auction: bool,
+    /// The number of cards left over after dealing that the napoleon picks up (and then discards
+    /// the same number), 0 means there is no kitty
+    #[serde(default)]
+    pub kitty_size: u32,
+    /// Fixed partnerships: the team of each player. When set the napoleon doesn't call any ally
+    /// cards and instead the rest of their team are their allies.
+    #[serde(default)]
+    pub teams: Option<Vec<usize>>,
+    #[serde(default)]
+    pub scoring: Scoring,
+    /// The number of packs of cards that are shuffled together. With more than one pack every
+    /// copy of a called ally card makes its holder an ally.
+    #[serde(default = "default_pack_count")]
+    pub pack_count: usize,
+    /// The number of jokers added to each pack, see `RuleSet::special_cards` for making them
+    /// beat the trumps
+    #[serde(default)]
+    pub jokers: usize,
+    /// If set then a number of rounds can be bid with no trumps (`Bid::NoTrump`), and this says
+    /// how it ranks against a bid of the same number of rounds with trumps
+    #[serde(default)]
+    pub no_trump: Option<NoTrumpRanking>,
+    /// Whether the napoleon may call a card that is in their own hand, which is one way of going
+    /// solo
+    #[serde(default = "default_allow_self_call")]
+    pub allow_self_call: bool,
+}
+
+fn default_pack_count() -> usize {
+    1
+}
+
+fn default_allow_self_call() -> bool {
+    true
+}
+
+/// A single pack of 5 card hands with one ally and nothing else turned on, the classic game for 4
+/// or 5 players. Use `GameSettings::builder` to work the settings out for the size of the table.
+impl Default for GameSettings {
+    fn default() -> GameSettings {
+        GameSettings {
+            ally_count: 1,
+            hand_size: 5,
+            rules: RuleSet::default(),
+            misere: None,
+            auction: false,
+            kitty_size: 0,
+            teams: None,
+            scoring: Scoring::default(),
+            pack_count: default_pack_count(),
+            jokers: 0,
+            no_trump: None,
+            allow_self_call: default_allow_self_call(),
+        }
+    }
 }
 
 impl Game {
     pub fn new(players: usize, settings: GameSettings) -> Game {
-        let mut deck = Deck::new(1);
-        deck.shuffle();
+        Self::with_rng(players, settings, &mut rand::thread_rng())
+    }
+
+    /// Creates a game whose seed is drawn from the given RNG, so a seeded RNG gives a reproducible
+    /// game.
+    pub fn with_rng<R: Rng + ?Sized>(players: usize, settings: GameSettings, rng: &mut R) -> Game {
+        Self::with_seed(players, settings, rng.gen())
+    }
+
+    /// Creates a game where the deal is entirely determined by the seed. The same seed (with the
+    /// same number of players and settings) always produces the same deal on every platform.
+    ///
+    /// The last player is the dealer so player 0 bids first, see `Game::with_dealer` to choose the
+    /// dealer.
+    pub fn with_seed(players: usize, settings: GameSettings, seed: u64) -> Game {
+        Self::with_dealer(players, settings, seed, players.saturating_sub(1))
+    }
+
+    /// Like `Game::with_seed` but with the given dealer, the player after the dealer bids first
+    /// and the dealer bids last.
+    ///
+    /// This panics if there aren't enough cards to deal, use `GameSettings::validate` (or build
+    /// the settings with `GameSettings::builder`) first.
+    pub fn with_dealer(players: usize, settings: GameSettings, seed: u64, dealer: usize) -> Game {
+        let mut deck = Deck::with_jokers(settings.pack_count, settings.jokers);
+        deck.shuffle_with(&mut ChaCha8Rng::seed_from_u64(seed));
 
         let mut hands = Vec::new();
         for _ in 0..players {
-            let mut hand = Deck::new_empty();
+            let mut hand = CardSet::new();
 
             for _ in 0..settings.hand_size {
-                hand.push(deck.pop().expect("Deck should have enough elements"));
+                hand.insert(deck.pop().expect("Deck should have enough elements"));
             }
 
             hands.push(hand);
         }
 
+        let mut kitty = Deck::new_empty();
+        for _ in 0..settings.kitty_size {
+            kitty.push(deck.pop().expect("Deck should have enough elements"));
+        }
+
+        Self::from_hands(
+            settings,
+            seed,
+            Deal {
+                dealer,
+                hands,
+                kitty,
+            },
+        )
+    }
+
+    fn from_hands(settings: GameSettings, seed: u64, deal: Deal) -> Game {
+        let Deal {
+            dealer,
+            hands,
+            kitty,
+        } = deal;
+        let players = hands.len();
+
         Game {
             players,
             hands,
+            kitty,
             score: vec![0; players],
+            settled_rounds: SettledRounds::default(),
+            bids: Vec::new(),
+            tricks: Vec::new(),
+            log: vec![Action::Deal { players, dealer }],
             state: GameState::Bidding {
-                current_player: 0,
+                current_player: (dealer + 1) % players,
                 current_napoleon: None,
+                passed: Vec::new(),
             },
             settings,
+            seed,
+            dealer,
         }
     }
 
@@ -120,22 +365,123 @@ impl Game {
         &self.settings
     }
 
-    pub fn get_hands(&self) -> &[Deck] {
+    pub fn get_hands(&self) -> &[CardSet] {
         &self.hands
     }
 
+    pub fn get_kitty(&self) -> &Deck {
+        &self.kitty
+    }
+
+    /// The number of rounds each player has won by playing them, rounds settled by a claim are
+    /// counted in `Game::get_settled_rounds` instead.
     pub fn get_score(&self) -> &[u32] {
         &self.score
     }
 
+    pub fn get_settled_rounds(&self) -> &SettledRounds {
+        &self.settled_rounds
+    }
+
+    pub fn get_bids(&self) -> &[(usize, Option<Bid>)] {
+        &self.bids
+    }
+
+    /// Every completed round in the order they were played.
+    pub fn get_tricks(&self) -> &[Trick] {
+        &self.tricks
+    }
+
+    /// Every successful action so far, starting with the deal (see `Game::replay`).
+    pub fn get_log(&self) -> &[Action] {
+        &self.log
+    }
+
+    /// The seed that the deal was generated from (see `Game::with_seed`).
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_dealer(&self) -> usize {
+        self.dealer
+    }
+
+    /// The player to the left of the dealer, who bids first.
+    pub fn first_bidder(&self) -> usize {
+        (self.dealer + 1) % self.players
+    }
+
+    /// Every bid the player could make right now, `None` being a pass. Empty if it isn't their
+    /// turn to bid.
+    pub fn legal_bids(&self, player_id: usize) -> Vec<Option<Bid>> {
+        if let GameState::Bidding {
+            current_player,
+            current_napoleon,
+            passed,
+        } = &self.state
+        {
+            if *current_player != player_id {
+                return Vec::new();
+            }
+
+            // The last player who can bid has to bid if no one else has, passing would end the
+            // bidding with no bids. That's the dealer, or in an auction whoever hasn't passed.
+            let last_to_bid = if self.settings.auction {
+                (0..self.players).all(|id| id == player_id || passed.contains(&id))
+            } else {
+                player_id == self.dealer
+            };
+            let current = current_napoleon.as_ref().map(|napoleon| &napoleon.bid);
+            let can_pass = current.is_some() || !last_to_bid;
+            std::iter::once(None)
+                .filter(|_| can_pass)
+                .chain(
+                    Bid::all(&self.settings)
+                        .filter(|bid| bid.outbids(current, &self.settings))
+                        .map(Some),
+                )
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Every card the player could legally play right now. Empty if it isn't their turn to play.
+    pub fn legal_cards(&self, player_id: usize) -> Vec<Card> {
+        if let GameState::Playing {
+            current_player,
+            required_suit,
+            trump,
+            claim,
+            ..
+        } = &self.state
+        {
+            if *current_player != player_id || claim.is_some() {
+                return Vec::new();
+            }
+
+            let hand = &self.hands[player_id];
+            hand.iter()
+                .filter(|card| {
+                    self.settings
+                        .rules
+                        .can_play(hand, card, required_suit, trump)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        }
+    }
+
     pub fn bid(
         &mut self,
         player_id: usize,
-        bid: Option<u32>,
+        bid: Option<Bid>,
     ) -> Result<BiddingEvent, BiddingError> {
         if let GameState::Bidding {
             current_player,
             current_napoleon,
+            passed,
         } = &mut self.state
         {
             if *current_player != player_id {
@@ -144,47 +490,126 @@ impl Game {
                 });
             }
 
-            match bid {
-                Some(bid) => {
-                    if bid > self.settings.hand_size {
-                        return Err(BiddingError::BidTooHigh {
-                            max: self.settings.hand_size,
-                        });
-                    }
+            let hand_size = self.settings.hand_size;
+            let bid = bid.map(|bid| bid.normalise(hand_size));
 
-                    if let Some(napoleon) = current_napoleon {
-                        if bid <= napoleon.bid {
-                            return Err(BiddingError::BidTooLow {
-                                min: napoleon.bid + 1,
-                            });
-                        }
-                    }
+            if let Some(bid) = &bid {
+                bid::check_bid(
+                    bid,
+                    current_napoleon.as_ref().map(|napoleon| &napoleon.bid),
+                    &self.settings,
+                )?;
+
+                *current_napoleon = Some(Napoleon {
+                    player_id,
+                    bid: bid.clone(),
+                });
+            }
+
+            let finished = if self.settings.auction {
+                // The auction carries on around the table until everyone apart from the highest
+                // bidder has passed
+                let still_bidding = (0..self.players)
+                    .filter(|id| !(passed.contains(id) || bid.is_none() && *id == player_id))
+                    .count();
 
-                    *current_napoleon = Some(Napoleon { player_id, bid });
+                if current_napoleon.is_some() {
+                    still_bidding <= 1
+                } else {
+                    still_bidding == 0
                 }
-                None => {}
+            } else {
+                player_id == self.dealer
+            };
+
+            if finished && current_napoleon.is_none() {
+                return Err(BiddingError::NoBids);
+            }
+
+            if bid.is_none() {
+                passed.push(player_id);
             }
 
-            if player_id == self.players - 1 {
+            self.bids.push((player_id, bid.clone()));
+            self.log.push(Action::Bid { player_id, bid });
+
+            if finished {
                 if let Some(napoleon) = current_napoleon {
                     let napoleon = napoleon.clone();
 
-                    self.state = GameState::PostBidding {
-                        napoleon: napoleon.clone(),
+                    self.state = if self.kitty.is_empty() {
+                        GameState::PostBidding {
+                            napoleon: napoleon.clone(),
+                        }
+                    } else {
+                        for card in self.kitty.iter() {
+                            self.hands[napoleon.player_id].insert(card.clone());
+                        }
+
+                        GameState::Exchanging {
+                            napoleon: napoleon.clone(),
+                        }
                     };
 
-                    return Ok(BiddingEvent::BiddingFinished { napoleon });
-                } else {
-                    return Err(BiddingError::NoBids);
+                    return Ok(BiddingEvent::BiddingFinished {
+                        napoleon,
+                        kitty: self.kitty.clone(),
+                    });
                 }
             }
 
-            *current_player += 1;
-            return Ok(BiddingEvent::NextBidder {
+            *current_player = (*current_player + 1) % self.players;
+            while passed.contains(current_player) {
+                *current_player = (*current_player + 1) % self.players;
+            }
+
+            Ok(BiddingEvent::NextBidder {
                 player_id: *current_player,
-            });
+            })
+        } else {
+            Err(BiddingError::InvalidGameState)
+        }
+    }
+
+    /// The napoleon discards as many cards as there were in the kitty (which they picked up at
+    /// the end of bidding).
+    pub fn discard(
+        &mut self,
+        player_id: usize,
+        cards: Vec<Card>,
+    ) -> Result<ExchangeEvent, ExchangeError> {
+        if let GameState::Exchanging { napoleon } = &self.state {
+            if napoleon.player_id != player_id {
+                return Err(ExchangeError::NotCurrentPlayer {
+                    current_player: napoleon.player_id,
+                });
+            }
+
+            if cards.len() != self.kitty.len() {
+                return Err(ExchangeError::IncorrectDiscardCount {
+                    expected: self.kitty.len(),
+                    received: cards.len(),
+                });
+            }
+
+            // Check against a copy of the hand so that the same card can't be discarded twice and
+            // the hand is left untouched if any card is missing
+            let mut hand = self.hands[player_id];
+            for card in &cards {
+                if !hand.remove(card) {
+                    return Err(ExchangeError::CardNotInHand);
+                }
+            }
+
+            self.hands[player_id] = hand;
+            self.log.push(Action::Discard { player_id, cards });
+            self.state = GameState::PostBidding {
+                napoleon: napoleon.clone(),
+            };
+
+            Ok(ExchangeEvent::KittyDiscarded)
         } else {
-            return Err(BiddingError::InvalidGameState);
+            Err(ExchangeError::InvalidGameState)
         }
     }
 
@@ -192,7 +617,7 @@ impl Game {
         &mut self,
         player_id: usize,
         ally_cards: Vec<Card>,
-        trump_suit: Suit,
+        trump: Trump,
     ) -> Result<PostBiddingEvent, PostBiddingError> {
         if let GameState::PostBidding { napoleon } = &self.state {
             if napoleon.player_id != player_id {
@@ -201,18 +626,61 @@ impl Game {
                 });
             }
 
-            if ally_cards.len() != self.settings.ally_count {
+            // With fixed partnerships the napoleon's team mates are their allies so no cards are
+            // called
+            let expected_ally_count = if self.settings.teams.is_some() {
+                0
+            } else {
+                self.settings.ally_count
+            };
+
+            if ally_cards.len() != expected_ally_count {
                 return Err(PostBiddingError::IncorrectAllyCount {
-                    expected: self.settings.ally_count,
+                    expected: expected_ally_count,
                     received: ally_cards.len(),
                 });
             }
 
+            if trump == Trump::Suit(Suit::Joker) {
+                return Err(PostBiddingError::InvalidTrumpSuit);
+            }
+
+            match (&napoleon.bid, &trump) {
+                (Bid::NoTrump(_), Trump::Suit(_)) => return Err(PostBiddingError::NoTrumpBid),
+                (_, Trump::NoTrump) if !matches!(napoleon.bid, Bid::NoTrump(_)) => {
+                    return Err(PostBiddingError::NoTrumpNotBid)
+                }
+                _ => {}
+            }
+
+            if ally_cards
+                .iter()
+                .enumerate()
+                .any(|(i, card)| ally_cards[..i].contains(card))
+            {
+                return Err(PostBiddingError::DuplicateAllyCard);
+            }
+
+            let napoleon_hand = &self.hands[napoleon.player_id];
+            if !self.settings.allow_self_call
+                && ally_cards.iter().any(|card| napoleon_hand.contains(card))
+            {
+                return Err(PostBiddingError::CalledOwnCard);
+            }
+
             let mut allies = Vec::new();
 
+            if let Some(teams) = &self.settings.teams {
+                let team = teams.get(napoleon.player_id);
+                allies.extend(
+                    (0..self.players)
+                        .filter(|id| *id != napoleon.player_id && teams.get(*id) == team),
+                );
+            }
+
             'outer: for (id, hand) in self.hands.iter().enumerate() {
-                // Napoleon can't pick themselves as an ally. Not an error just skip adding
-                // napoleon to allies vector
+                // Napoleon can't pick themselves as an ally, calling their own card is one way of
+                // going solo
                 if id == napoleon.player_id {
                     continue 'outer;
                 }
@@ -226,121 +694,182 @@ impl Game {
                 }
             }
 
+            // Only a napoleon who called cards can go solo, with no cards to call (or fixed
+            // partnerships) playing alone is just the normal game
+            let solo = !ally_cards.is_empty() && allies.is_empty();
+
+            self.log.push(Action::PickAllies {
+                player_id,
+                ally_cards: ally_cards.clone(),
+                trump: trump.clone(),
+            });
+
             self.state = GameState::Playing {
                 napoleon: napoleon.clone(),
                 allies: allies.clone(),
-                required_suit: Some(trump_suit.clone()),
-                trump_suit,
+                ally_cards,
+                // Partnerships are public so every ally is known from the start
+                revealed_allies: if self.settings.teams.is_some() {
+                    allies.clone()
+                } else {
+                    Vec::new()
+                },
+                solo,
+                claim: None,
+                required_suit: self.settings.rules.first_required_suit(&trump),
+                trump,
                 current_player: napoleon.player_id,
                 played_cards: Vec::with_capacity(self.players),
             };
 
-            Ok(PostBiddingEvent::AlliesChosen { allies })
+            Ok(PostBiddingEvent::AlliesChosen { allies, solo })
         } else {
             Err(PostBiddingError::InvalidGameState)
         }
     }
 
+    /// Plays a card for the player, returning what happened in order: `AllyRevealed` if this
+    /// revealed an ally and then the next player, the end of the round or the end of the game.
     pub fn play_card(
         &mut self,
         player_id: usize,
         card: Card,
-    ) -> Result<PlayingEvent, PlayingError> {
+    ) -> Result<Vec<PlayingEvent>, PlayingError> {
         if let GameState::Playing {
             allies,
+            ally_cards,
+            revealed_allies,
             current_player,
             played_cards,
-            trump_suit,
+            trump,
             napoleon,
             required_suit,
-            ..
+            solo,
+            claim,
         } = &mut self.state
         {
+            if claim.is_some() {
+                return Err(PlayingError::ClaimPending);
+            }
+
             if player_id != *current_player {
                 return Err(PlayingError::NotCurrentPlayer {
                     current_player: *current_player,
                 });
             }
 
-            if self.hands[player_id].remove(&card).is_some() {
+            if !self.hands[player_id].contains(&card) {
                 return Err(PlayingError::CardNotInHand);
             }
 
             // Ensure that the required suit is played if they have a card of that suit and if
             // there is in fact a required suit (there isn't for the first player of a round except
-            // for the first round)
-            if let Some(required_suit) = required_suit {
-                if &card.suit != required_suit && self.hands[player_id].contains_suit(required_suit)
-                {
-                    return Err(PlayingError::InvalidSuit);
-                }
-            } else {
+            // for the first round when the rules force a trump lead)
+            if !self
+                .settings
+                .rules
+                .can_play(&self.hands[player_id], &card, required_suit, trump)
+            {
+                return Err(PlayingError::InvalidSuit);
+            }
+
+            self.hands[player_id].remove(&card);
+            self.log.push(Action::PlayCard {
+                player_id,
+                card: card.clone(),
+            });
+
+            if required_suit.is_none() && !card.is_joker() {
                 // If there wasn't a required suit then all next cards should have the same suit as
-                // the current (since it's the first of a round)
+                // the current (since it's the first of a round). A joker doesn't belong to a suit
+                // so leading one leaves the suit to the next card played.
                 *required_suit = Some(card.suit.clone());
             }
 
-            played_cards.push(card);
-
-            if played_cards.len() == self.players {
-                // TODO: Even though deck supports multiple packs of cards, scoring does not.
-                // It is unclear what to do when two players both have the exact same card that is
-                // the highest number + trump suit.
-                let (winner, _card) = played_cards
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, card)| &card.suit == trump_suit)
-                    .max_by_key(|(_, card)| Into::<u8>::into(&card.number))
-                    .unwrap_or_else(|| {
-                        played_cards
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, card)| card.suit == played_cards[0].suit)
-                            .max_by_key(|(_, card)| Into::<u8>::into(&card.number))
-                            .expect("The first player's card trivially must exist as a possible solution")
-                    });
+            let mut events = Vec::new();
+
+            // Playing one of the ally cards reveals to everyone that the player is an ally
+            if allies.contains(&player_id)
+                && ally_cards.contains(&card)
+                && !revealed_allies.contains(&player_id)
+            {
+                revealed_allies.push(player_id);
+                events.push(PlayingEvent::AllyRevealed {
+                    player_id,
+                    card: card.clone(),
+                });
+            }
+
+            played_cards.push((player_id, card));
+
+            let event = if played_cards.len() == self.players {
+                let trick =
+                    Trick::complete(std::mem::take(played_cards), trump, &self.settings.rules);
+                let winner = trick.winner;
 
                 self.score[winner] += 1;
+                self.tricks.push(trick.clone());
 
-                played_cards.clear();
-
-                if self.hands[0].len() == 0 {
-                    let combined_napoleon_score = self.score[napoleon.player_id]
-                        + self
-                            .score
-                            .iter()
-                            .enumerate()
-                            .filter_map(|(id, score)| {
-                                if allies.contains(&id) {
-                                    Some(score)
-                                } else {
-                                    None
-                                }
-                            })
-                            .sum::<u32>();
-                    return Ok(PlayingEvent::GameEnded {
-                        combined_napoleon_score,
-                        napoleon: napoleon.clone(),
-                        allies: allies.clone(),
-                    });
+                if self.hands[0].is_empty() {
+                    let (napoleon, allies, solo) = (napoleon.clone(), allies.clone(), *solo);
+                    self.game_ended(napoleon, allies, solo)
                 } else {
-                    // Now that the round has ended the next player has no required_suit
+                    // Now that the round has ended the winner starts the next round with no
+                    // required_suit
                     *required_suit = None;
-                    return Ok(PlayingEvent::RoundEnded {
+                    *current_player = winner;
+                    PlayingEvent::RoundEnded {
                         next_player: winner,
                         winner,
-                    });
+                        trick,
+                    }
                 }
             } else {
                 *current_player = (*current_player + 1) % self.players;
-                return Ok(PlayingEvent::NextPlayer {
+                PlayingEvent::NextPlayer {
                     player_id: *current_player,
-                    // Can never fail since earlier if required_suit was None is was set to Some.
-                    required_suit: required_suit.clone().unwrap(),
-                });
-            }
+                    required_suit: required_suit.clone(),
+                }
+            };
+            events.push(event);
+
+            Ok(events)
         } else {
             Err(PlayingError::InvalidGameState)
         }
     }
+
+    /// The `GameEnded` event for the rounds that have been won so far.
+    fn game_ended(&self, napoleon: Napoleon, allies: Vec<usize>, solo: bool) -> PlayingEvent {
+        let combined_napoleon_score = self.score[napoleon.player_id]
+            + self.settled_rounds.napoleon_side
+            + self
+                .score
+                .iter()
+                .enumerate()
+                .filter_map(|(id, score)| {
+                    if allies.contains(&id) {
+                        Some(score)
+                    } else {
+                        None
+                    }
+                })
+                .sum::<u32>();
+
+        PlayingEvent::GameEnded {
+            contract_made: napoleon
+                .bid
+                .is_made(combined_napoleon_score, self.settings.hand_size),
+            score: self.settings.scoring.score(
+                &napoleon.bid,
+                combined_napoleon_score,
+                solo,
+                &self.settings,
+            ),
+            combined_napoleon_score,
+            napoleon,
+            allies,
+            solo,
+        }
+    }
 }
@@ -0,0 +1,151 @@
+use crate::deck::{Card, Number, Suit, Trump};
+use crate::CardSet;
+
+use serde::{Deserialize, Serialize};
+
+/// The house rules that a game is played with. `RuleSet::default()` gives the standard rules.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleSet {
+    pub first_lead: FirstLead,
+    pub follow_suit: FollowSuit,
+    pub ranking: Ranking,
+    pub duplicates: Duplicates,
+    /// Cards that beat every trump, highest first, e.g. a joker or a "mighty" ace of spades. These
+    /// (and jokers) can be played at any time.
+    pub special_cards: Vec<Card>,
+}
+
+/// What the napoleon has to lead in the very first round.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FirstLead {
+    /// The napoleon must lead a trump if they have one
+    #[default]
+    Trump,
+    /// The napoleon may lead any card
+    Any,
+}
+
+/// What a player is allowed to play once a suit has been led.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FollowSuit {
+    /// Players must follow suit if they can, otherwise they may play anything
+    #[default]
+    Required,
+    /// Like `Required` but a trump may be played at any time even if the player could follow suit
+    TrumpAnytime,
+    /// Like `Required` but a player who can't follow suit must play a trump if they have one
+    MustTrump,
+}
+
+/// The order of the cards within a suit.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Ranking {
+    #[default]
+    AceHigh,
+    AceLow,
+}
+
+/// Which card wins when identical cards (from playing with more than one pack) tie for the win.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Duplicates {
+    #[default]
+    FirstPlayedWins,
+    LastPlayedWins,
+}
+
+impl RuleSet {
+    /// The suit that must be followed in the first round.
+    pub fn first_required_suit(&self, trump: &Trump) -> Option<Suit> {
+        match self.first_lead {
+            FirstLead::Trump => trump.suit().cloned(),
+            FirstLead::Any => None,
+        }
+    }
+
+    /// Whether `card` (which must be in `hand`) can be played given the suit that was led.
+    pub fn can_play(
+        &self,
+        hand: &CardSet,
+        card: &Card,
+        required_suit: &Option<Suit>,
+        trump: &Trump,
+    ) -> bool {
+        let required_suit = if let Some(required_suit) = required_suit {
+            required_suit
+        } else {
+            return true;
+        };
+
+        if card.is_joker() || self.special_cards.contains(card) {
+            return true;
+        }
+
+        if &card.suit == required_suit {
+            return true;
+        }
+
+        let is_trump = trump.suit() == Some(&card.suit);
+        if hand.contains_suit(required_suit) {
+            return self.follow_suit == FollowSuit::TrumpAnytime && is_trump;
+        }
+
+        match self.follow_suit {
+            FollowSuit::MustTrump => {
+                is_trump || trump.suit().is_none_or(|suit| !hand.contains_suit(suit))
+            }
+            FollowSuit::Required | FollowSuit::TrumpAnytime => true,
+        }
+    }
+
+    /// The rank of a number within its suit, a higher rank beats a lower one.
+    pub fn rank(&self, number: &Number) -> u8 {
+        match (&self.ranking, number) {
+            (Ranking::AceLow, Number::Ace) => 1,
+            _ => number.into(),
+        }
+    }
+
+    /// Returns the index into `cards` of the card that wins the round: the highest special card if
+    /// there are any, then the highest trump, otherwise the highest card of the led suit. If that
+    /// card was played more than once then `duplicates` decides which copy wins.
+    pub fn winning_card(&self, cards: &[Card], trump: &Trump) -> usize {
+        assert!(!cards.is_empty(), "A round must have at least one card");
+        let led_suit = led_suit(cards);
+
+        // Only identical cards can have the same strength
+        let strength = |card: &Card| {
+            if let Some(i) = self.special_cards.iter().position(|c| c == card) {
+                (3, self.special_cards.len() - i)
+            } else if trump.suit() == Some(&card.suit) {
+                (2, self.rank(&card.number) as usize)
+            } else if &card.suit == led_suit {
+                (1, self.rank(&card.number) as usize)
+            } else {
+                (0, 0)
+            }
+        };
+
+        let mut winner = 0;
+        for (i, card) in cards.iter().enumerate().skip(1) {
+            let (card_strength, winner_strength) = (strength(card), strength(&cards[winner]));
+            if card_strength > winner_strength
+                || (card_strength == winner_strength
+                    && self.duplicates == Duplicates::LastPlayedWins)
+            {
+                winner = i;
+            }
+        }
+
+        winner
+    }
+}
+
+/// The suit that a round calls for, which is the suit of the first card that isn't a joker since
+/// leading a joker leaves the suit to the next card. Jokers if every card is a joker.
+pub(crate) fn led_suit(cards: &[Card]) -> &Suit {
+    cards
+        .iter()
+        .find(|card| !card.is_joker())
+        .map_or(&Suit::Joker, |card| &card.suit)
+}
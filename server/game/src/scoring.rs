@@ -0,0 +1,112 @@
+use crate::{Bid, GameSettings};
+
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// How the points for a finished game are worked out.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scoring {
+    pub scheme: ScoringScheme,
+    /// Multiply the points by `Bid::multiplier` so that naps, wellingtons and blüchers are played
+    /// for higher stakes
+    pub stakes: bool,
+    /// Extra points for a napoleon who goes solo (nobody else holds a called card) and makes the
+    /// contract, added before the stakes multiplier
+    pub solo_bonus: i32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ScoringScheme {
+    /// The napoleon's side wins 15 points and everyone else loses 10, or the other way around if
+    /// the contract is lost
+    #[default]
+    Flat,
+    /// The points won or lost are the number of rounds that were bid
+    PerBid,
+    /// Like `PerBid` but the napoleon's side also wins a point for every round won over the
+    /// contract
+    PerOvertrick,
+}
+
+/// The change in score for one game, the napoleon and their allies all get `napoleon_side` and
+/// every other player gets `opponents`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub napoleon_side: i32,
+    pub opponents: i32,
+}
+
+impl Scoring {
+    /// Scores a finished game given the number of rounds won by the napoleon and their allies and
+    /// whether the napoleon went solo.
+    pub fn score(
+        &self,
+        bid: &Bid,
+        combined_napoleon_score: u32,
+        solo: bool,
+        settings: &GameSettings,
+    ) -> Score {
+        let made = bid.is_made(combined_napoleon_score, settings.hand_size);
+        let level = match bid {
+            // A misère is worth the same as the bid that it ranks just above
+            Bid::Misere => settings.misere.unwrap_or(0).max(1),
+            bid => bid.tricks(settings.hand_size),
+        };
+        let level = points(level);
+
+        let (won, lost) = match self.scheme {
+            ScoringScheme::Flat => (15, 10),
+            ScoringScheme::PerBid => (level, level),
+            ScoringScheme::PerOvertrick => {
+                let overtricks = match bid {
+                    Bid::Misere => 0,
+                    bid => combined_napoleon_score.saturating_sub(bid.tricks(settings.hand_size)),
+                };
+                (level.saturating_add(points(overtricks)), level)
+            }
+        };
+
+        let multiplier = if self.stakes {
+            points(bid.multiplier())
+        } else {
+            1
+        };
+
+        // Settings from a host aren't always validated so the points saturate rather than overflow
+        let stake = |points: i32| points.saturating_mul(multiplier);
+        if made {
+            // Only a napoleon who makes the contract gets the bonus for going solo
+            let bonus = if solo { self.solo_bonus } else { 0 };
+            Score {
+                napoleon_side: stake(won.saturating_add(bonus)),
+                opponents: stake(lost).saturating_neg(),
+            }
+        } else {
+            Score {
+                napoleon_side: stake(lost).saturating_neg(),
+                opponents: stake(won),
+            }
+        }
+    }
+}
+
+impl Score {
+    /// The change in score for each player.
+    pub fn deltas(&self, players: usize, napoleon: usize, allies: &[usize]) -> Vec<i32> {
+        (0..players)
+            .map(|player_id| {
+                if player_id == napoleon || allies.contains(&player_id) {
+                    self.napoleon_side
+                } else {
+                    self.opponents
+                }
+            })
+            .collect()
+    }
+}
+
+/// A number of rounds as points, capped instead of wrapping around to a negative score.
+fn points(rounds: u32) -> i32 {
+    i32::try_from(rounds).unwrap_or(i32::MAX)
+}
@@ -0,0 +1,243 @@
+use crate::{GameSettings, NoTrumpRanking, RuleSet, Scoring};
+
+use serde::{Deserialize, Serialize};
+
+/// The most cards that are dealt to each player when the hand size is worked out automatically.
+const DEFAULT_HAND_SIZE: u32 = 5;
+/// The most packs that can be shuffled together.
+pub const MAX_PACK_COUNT: usize = 8;
+/// The most jokers that can be added to each pack.
+pub const MAX_JOKERS: usize = 4;
+/// The largest bonus or penalty for going solo.
+pub const MAX_SOLO_BONUS: u32 = 1000;
+
+/// Why the settings can't be used for a game with the given number of players.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SettingsError {
+    TooFewPlayers {
+        min: usize,
+    },
+    /// The hand size was 0
+    EmptyHands,
+    /// The hands and the kitty need more cards than there are in the deck
+    NotEnoughCards {
+        needed: usize,
+        available: usize,
+    },
+    /// There must be at least one player who isn't on the napoleon's side
+    TooManyAllies {
+        max: usize,
+    },
+    /// The teams have to give a team for every player, and not everyone can be on the same team
+    InvalidTeams,
+    TooManyPacks {
+        max: usize,
+    },
+    TooManyJokers {
+        max: usize,
+    },
+    /// The number of cards needed is too large to count
+    TooManyCards,
+    /// A misère has to rank below a bid for every round in the hand
+    InvalidMisere {
+        max: u32,
+    },
+    SoloBonusTooLarge {
+        max: u32,
+    },
+}
+
+/// The number of cards in a deck of this many packs each with this many jokers.
+fn deck_size(pack_count: usize, jokers: usize) -> Result<usize, SettingsError> {
+    if pack_count > MAX_PACK_COUNT {
+        return Err(SettingsError::TooManyPacks {
+            max: MAX_PACK_COUNT,
+        });
+    }
+    if jokers > MAX_JOKERS {
+        return Err(SettingsError::TooManyJokers { max: MAX_JOKERS });
+    }
+
+    52usize
+        .checked_add(jokers)
+        .and_then(|pack_size| pack_size.checked_mul(pack_count))
+        .ok_or(SettingsError::TooManyCards)
+}
+
+/// Builds `GameSettings` for a number of players, any setting that isn't given is filled in with
+/// a sensible default for the size of the table.
+///
+/// The builder can also be deserialized from the same JSON as `GameSettings` except that every
+/// field is optional.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameSettingsBuilder {
+    ally_count: Option<usize>,
+    hand_size: Option<u32>,
+    rules: RuleSet,
+    misere: Option<u32>,
+    auction: bool,
+    kitty_size: u32,
+    teams: Option<Vec<usize>>,
+    scoring: Scoring,
+    pack_count: Option<usize>,
+    jokers: usize,
+    no_trump: Option<NoTrumpRanking>,
+    allow_self_call: Option<bool>,
+}
+
+impl GameSettings {
+    pub fn builder() -> GameSettingsBuilder {
+        GameSettingsBuilder::default()
+    }
+
+    /// The number of cards in the deck that the game is dealt from, fails if there are too many
+    /// packs or jokers.
+    pub fn deck_size(&self) -> Result<usize, SettingsError> {
+        deck_size(self.pack_count, self.jokers)
+    }
+
+    /// Checks that a game can be dealt and played with these settings and this many players.
+    pub fn validate(&self, players: usize) -> Result<(), SettingsError> {
+        if players < 2 {
+            return Err(SettingsError::TooFewPlayers { min: 2 });
+        }
+
+        if self.hand_size == 0 {
+            return Err(SettingsError::EmptyHands);
+        }
+
+        let available = self.deck_size()?;
+        let needed = (self.hand_size as usize)
+            .checked_mul(players)
+            .and_then(|dealt| dealt.checked_add(self.kitty_size as usize))
+            .ok_or(SettingsError::TooManyCards)?;
+        if needed > available {
+            return Err(SettingsError::NotEnoughCards { needed, available });
+        }
+
+        if let Some(misere) = self.misere {
+            if misere >= self.hand_size {
+                return Err(SettingsError::InvalidMisere {
+                    max: self.hand_size - 1,
+                });
+            }
+        }
+
+        if self.scoring.solo_bonus.unsigned_abs() > MAX_SOLO_BONUS {
+            return Err(SettingsError::SoloBonusTooLarge {
+                max: MAX_SOLO_BONUS,
+            });
+        }
+
+        if self.teams.is_none() && self.ally_count >= players - 1 {
+            return Err(SettingsError::TooManyAllies { max: players - 2 });
+        }
+
+        if let Some(teams) = &self.teams {
+            if teams.len() != players || teams.iter().all(|team| *team == teams[0]) {
+                return Err(SettingsError::InvalidTeams);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl GameSettingsBuilder {
+    pub fn ally_count(mut self, ally_count: usize) -> Self {
+        self.ally_count = Some(ally_count);
+        self
+    }
+
+    pub fn hand_size(mut self, hand_size: u32) -> Self {
+        self.hand_size = Some(hand_size);
+        self
+    }
+
+    pub fn rules(mut self, rules: RuleSet) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn misere(mut self, misere: Option<u32>) -> Self {
+        self.misere = misere;
+        self
+    }
+
+    pub fn auction(mut self, auction: bool) -> Self {
+        self.auction = auction;
+        self
+    }
+
+    pub fn kitty_size(mut self, kitty_size: u32) -> Self {
+        self.kitty_size = kitty_size;
+        self
+    }
+
+    pub fn teams(mut self, teams: Option<Vec<usize>>) -> Self {
+        self.teams = teams;
+        self
+    }
+
+    pub fn scoring(mut self, scoring: Scoring) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    pub fn pack_count(mut self, pack_count: usize) -> Self {
+        self.pack_count = Some(pack_count);
+        self
+    }
+
+    pub fn jokers(mut self, jokers: usize) -> Self {
+        self.jokers = jokers;
+        self
+    }
+
+    pub fn no_trump(mut self, no_trump: Option<NoTrumpRanking>) -> Self {
+        self.no_trump = no_trump;
+        self
+    }
+
+    pub fn allow_self_call(mut self, allow_self_call: bool) -> Self {
+        self.allow_self_call = Some(allow_self_call);
+        self
+    }
+
+    /// Fills in the hand size and ally count if they weren't given and then validates the
+    /// settings.
+    ///
+    /// The hand size defaults to 5 cards, or fewer if the deck doesn't have enough cards for
+    /// everyone, and about a third of the table is on the napoleon's side.
+    pub fn build(self, players: usize) -> Result<GameSettings, SettingsError> {
+        let pack_count = self.pack_count.unwrap_or(1);
+        let deck_size = deck_size(pack_count, self.jokers)?;
+
+        let hand_size = self.hand_size.unwrap_or_else(|| {
+            let available = deck_size.saturating_sub(self.kitty_size as usize);
+            (available / players.max(1)).min(DEFAULT_HAND_SIZE as usize) as u32
+        });
+        let ally_count = self
+            .ally_count
+            .unwrap_or_else(|| players.saturating_sub(1) / 3);
+
+        let settings = GameSettings {
+            ally_count,
+            hand_size,
+            rules: self.rules,
+            misere: self.misere,
+            auction: self.auction,
+            kitty_size: self.kitty_size,
+            teams: self.teams,
+            scoring: self.scoring,
+            pack_count,
+            jokers: self.jokers,
+            no_trump: self.no_trump,
+            allow_self_call: self.allow_self_call.unwrap_or(true),
+        };
+        settings.validate(players)?;
+
+        Ok(settings)
+    }
+}
@@ -0,0 +1,41 @@
+use crate::Game;
+
+use serde::{Deserialize, Serialize};
+
+/// The version of the snapshot format produced by `Game::snapshot`.
+///
+/// This must be bumped whenever the serialized layout of `Game` changes so that snapshots from an
+/// older version of the engine are rejected rather than restored incorrectly.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A complete, serializable copy of a game in progress (including every player's hand).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub game: Game,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SnapshotError {
+    UnsupportedVersion { version: u32, supported: u32 },
+}
+
+impl Game {
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            game: self.clone(),
+        }
+    }
+
+    pub fn restore(snapshot: Snapshot) -> Result<Game, SnapshotError> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion {
+                version: snapshot.version,
+                supported: SNAPSHOT_VERSION,
+            });
+        }
+
+        Ok(snapshot.game)
+    }
+}
@@ -0,0 +1,32 @@
+use crate::rules::led_suit;
+use crate::{Card, RuleSet, Suit, Trump};
+
+use serde::{Deserialize, Serialize};
+
+/// A completed round of play: one card from every player.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Trick {
+    /// The player who played the first card
+    pub leader: usize,
+    /// Each player and the card they played in the order they were played
+    pub cards: Vec<(usize, Card)>,
+    /// The suit of the first card that wasn't a joker, since a joker lead leaves the suit to the
+    /// next card
+    pub led_suit: Suit,
+    pub winner: usize,
+}
+
+impl Trick {
+    /// Completes a trick from the cards that were played, working out the winner using the rules.
+    pub(crate) fn complete(cards: Vec<(usize, Card)>, trump: &Trump, rules: &RuleSet) -> Trick {
+        let played: Vec<Card> = cards.iter().map(|(_, card)| card.clone()).collect();
+        let winner = cards[rules.winning_card(&played, trump)].0;
+
+        Trick {
+            leader: cards[0].0,
+            led_suit: led_suit(&played).clone(),
+            cards,
+            winner,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Bid, Card, Claim, Deck, Game, GameState, Napoleon, SettledRounds, Trick, Trump};
+
+use serde::{Deserialize, Serialize};
+
+/// Everything that a single player is allowed to know about the game, i.e. their information set.
+///
+/// This never contains the other players' hands or the identity of allies that have not yet been
+/// revealed, so it is safe to hand to bots, spectators or reconnecting clients.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub player_id: usize,
+    pub hand: Deck,
+    /// Every bid (or pass) so far in the order they were made
+    pub bids: Vec<(usize, Option<Bid>)>,
+    pub napoleon: Option<Napoleon>,
+    pub trump: Option<Trump>,
+    pub ally_cards: Vec<Card>,
+    /// The allies known to this player: the ones that have been revealed and the player
+    /// themselves if they are an ally
+    pub allies: Vec<usize>,
+    /// The allies that have revealed themselves by playing a called card, with the card they
+    /// played, in the order they were revealed
+    pub revealed_allies: Vec<(usize, Card)>,
+    /// The cards played so far in the current round along with who played them
+    pub played_cards: Vec<(usize, Card)>,
+    /// Every completed round
+    pub tricks: Vec<Trick>,
+    /// The number of rounds won by each player
+    pub tricks_won: Vec<u32>,
+    /// The rounds that were settled by a claim rather than won by anyone
+    pub settled_rounds: SettledRounds,
+    /// The player whose turn it is to bid, pick allies or play a card
+    pub current_player: usize,
+    pub dealer: usize,
+    /// A claim that is waiting for the players to accept or reject it
+    pub claim: Option<Claim>,
+}
+
+impl Game {
+    pub fn view_for(&self, player_id: usize) -> PlayerView {
+        let mut view = PlayerView {
+            player_id,
+            hand: Deck::from(&self.hands[player_id]),
+            bids: self.bids.clone(),
+            napoleon: None,
+            trump: None,
+            ally_cards: Vec::new(),
+            allies: Vec::new(),
+            revealed_allies: Vec::new(),
+            played_cards: Vec::new(),
+            tricks: self.tricks.clone(),
+            tricks_won: self.score.clone(),
+            settled_rounds: self.settled_rounds.clone(),
+            current_player: 0,
+            dealer: self.dealer,
+            claim: None,
+        };
+
+        match &self.state {
+            GameState::Bidding {
+                current_player,
+                current_napoleon,
+                ..
+            } => {
+                view.napoleon = current_napoleon.clone();
+                view.current_player = *current_player;
+            }
+            GameState::Exchanging { napoleon } | GameState::PostBidding { napoleon } => {
+                view.napoleon = Some(napoleon.clone());
+                view.current_player = napoleon.player_id;
+            }
+            GameState::Playing {
+                napoleon,
+                allies,
+                ally_cards,
+                revealed_allies,
+                trump,
+                current_player,
+                played_cards,
+                claim,
+                ..
+            } => {
+                view.claim = claim.clone();
+                view.napoleon = Some(napoleon.clone());
+                view.trump = Some(trump.clone());
+                view.ally_cards = ally_cards.clone();
+                view.allies = revealed_allies.clone();
+                if allies.contains(&player_id) && !view.allies.contains(&player_id) {
+                    view.allies.push(player_id);
+                }
+                view.played_cards = played_cards.clone();
+                view.current_player = *current_player;
+
+                let played = self
+                    .tricks
+                    .iter()
+                    .flat_map(|trick| trick.cards.iter())
+                    .chain(played_cards.iter());
+                for (id, card) in played {
+                    if allies.contains(id)
+                        && ally_cards.contains(card)
+                        && !view.revealed_allies.iter().any(|(ally, _)| ally == id)
+                    {
+                        view.revealed_allies.push((*id, card.clone()));
+                    }
+                }
+            }
+        }
+
+        view
+    }
+}
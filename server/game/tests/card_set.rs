@@ -0,0 +1,77 @@
+use game::deck::{Card, Deck, Number::*, ParseCardError, Suit::*};
+use game::{CardSet, MAX_JOKERS, MAX_PACK_COUNT};
+
+fn set(cards: Vec<Card>) -> CardSet {
+    cards.into_iter().collect()
+}
+
+#[test]
+fn test_card_set() {
+    let full = CardSet::from(&Deck::with_jokers(1, 1));
+    assert_eq!(full.len(), 53);
+    assert!(full.contains(&Card::joker()));
+    assert_eq!(full.suit(&Spades).len(), 13);
+
+    let mut hand = set(vec![
+        Card::new(Spades, Ace),
+        Card::new(Hearts, Two),
+        Card::new(Spades, Two),
+    ]);
+    assert!(hand.contains_suit(&Spades));
+    assert!(!hand.contains_suit(&Clubs));
+    // Suits in order, lowest rank first
+    assert_eq!(
+        hand.iter().collect::<Vec<_>>(),
+        vec![
+            Card::new(Hearts, Two),
+            Card::new(Spades, Two),
+            Card::new(Spades, Ace)
+        ]
+    );
+
+    assert!(hand.remove(&Card::new(Hearts, Two)));
+    assert!(!hand.remove(&Card::new(Hearts, Two)));
+    assert!(!hand.contains_suit(&Hearts));
+    assert!(hand.is_subset(&full));
+    assert_eq!(Deck::from(&hand).len(), 2);
+}
+
+#[test]
+fn test_card_set_duplicates() {
+    let ace = Card::new(Spades, Ace);
+    let king = Card::new(Spades, King);
+
+    let mut two_aces = set(vec![ace.clone(), king.clone(), ace.clone()]);
+    assert_eq!(two_aces.len(), 3);
+    assert_eq!(two_aces.count(&ace), 2);
+
+    let one_ace = set(vec![ace.clone()]);
+    assert_eq!(two_aces.difference(&one_ace), set(vec![ace.clone(), king]));
+    assert_eq!(two_aces.intersection(&one_ace), one_ace);
+    assert_eq!(one_ace.union(&two_aces), two_aces);
+    assert!(one_ace.is_subset(&two_aces));
+    assert!(!two_aces.is_subset(&one_ace));
+
+    assert!(two_aces.remove(&ace));
+    assert!(two_aces.remove(&ace));
+    assert!(!two_aces.contains(&ace));
+    assert_eq!(two_aces.len(), 1);
+
+    // The set holds as many copies as the largest deck and no more
+    let largest = CardSet::from(&Deck::with_jokers(MAX_PACK_COUNT, MAX_JOKERS));
+    assert_eq!(
+        largest.len(),
+        Deck::with_jokers(MAX_PACK_COUNT, MAX_JOKERS).len()
+    );
+    assert_eq!(largest.count(&Card::joker()), MAX_PACK_COUNT * MAX_JOKERS);
+    let mut full = largest;
+    assert!(!full.insert(ace.clone()));
+    assert!(!full.insert(Card::joker()));
+    assert_eq!(full, largest);
+
+    let too_many = ["AS"; MAX_PACK_COUNT + 1].join(",");
+    assert_eq!(
+        too_many.parse::<CardSet>(),
+        Err(ParseCardError::TooManyCopies)
+    );
+}
@@ -1,6 +1,37 @@
-use game::deck::Deck;
+use game::deck::{Card, Deck, Number, ParseCardError, Suit};
 
 #[test]
 fn test_full() {
 
 }
+
+#[test]
+fn test_jokers() {
+    let deck = Deck::with_jokers(2, 2);
+    assert_eq!(deck.len(), 108);
+    assert_eq!(deck.iter().filter(|card| card.is_joker()).count(), 4);
+    assert_eq!(Card::from_chars('X', 'X'), Ok(Card::joker()));
+}
+
+#[test]
+fn test_notation() {
+    let card: Card = "TH".parse().unwrap();
+    assert_eq!(card, Card::new(Suit::Hearts, Number::Ten));
+    assert_eq!(card.to_string(), "TH");
+    assert_eq!("S".parse(), Ok(Suit::Spades));
+    assert_eq!("Q".parse(), Ok(Number::Queen));
+
+    assert_eq!(
+        "1H".parse::<Card>(),
+        Err(ParseCardError::InvalidNumber('1'))
+    );
+    assert_eq!("TZ".parse::<Card>(), Err(ParseCardError::InvalidSuit('Z')));
+    assert_eq!("THS".parse::<Card>(), Err(ParseCardError::InvalidLength));
+    assert_eq!("XH".parse::<Card>(), Err(ParseCardError::InvalidJoker));
+
+    let deck: Deck = "2C,AS,XX".parse().unwrap();
+    assert_eq!(deck.len(), 3);
+    assert_eq!(deck.to_string(), "2C,AS,XX");
+    assert!("".parse::<Deck>().unwrap().is_empty());
+    assert!("2C,,AS".parse::<Deck>().is_err());
+}
@@ -0,0 +1,1120 @@
+use game::deck::{Card, Deck, Number::*, Suit::*};
+use game::{
+    Bid, BiddingError, BiddingEvent, CardSet, ClaimError, ClaimEvent, ClaimKind, Deal, DealError,
+    ExchangeError, Game, GameSettings, GameSettingsBuilder, NoTrumpRanking, ParseDealError,
+    PlayingError, PlayingEvent, PostBiddingError, PostBiddingEvent, RuleSet, Score, Scoring,
+    ScoringScheme, SettingsError, SettledRounds, Trump,
+};
+
+/// Player 0 wins the bidding with `bid` while everyone else passes.
+fn win_bidding(game: &mut Game, bid: Bid) {
+    assert!(game.bid(0, Some(bid)).is_ok());
+    for player_id in 1..game.get_hands().len() {
+        assert!(game.bid(player_id, None).is_ok());
+    }
+}
+
+/// Player 0 wins the bidding with `bid` and then calls `called` to pick their allies.
+fn start_playing(game: &mut Game, bid: Bid, called: Card, trump: Trump) {
+    win_bidding(game, bid);
+    assert!(game.pick_allies(0, vec![called], trump).is_ok());
+}
+
+#[test]
+fn test_seeded_deal() {
+    let game = Game::with_seed(4, GameSettings::default(), 42);
+    assert_eq!(game.get_seed(), 42);
+    assert_eq!(
+        game.get_hands(),
+        Game::with_seed(4, GameSettings::default(), 42).get_hands()
+    );
+    assert_ne!(
+        game.get_hands(),
+        Game::with_seed(4, GameSettings::default(), 43).get_hands()
+    );
+}
+
+#[test]
+fn test_seeded_deal_is_stable() {
+    // Pins the exact deal for a seed so that any change to the shuffle (or the RNG) which would
+    // break reproducing old games is caught.
+    let settings = GameSettings {
+        ally_count: 1,
+        hand_size: 3,
+        ..Default::default()
+    };
+    let game = Game::with_seed(2, settings, 0);
+
+    let hand = |cards: Vec<Card>| cards.into_iter().collect::<CardSet>();
+
+    assert_eq!(
+        game.get_hands(),
+        &[
+            hand(vec![
+                Card::new(Spades, Ten),
+                Card::new(Spades, Queen),
+                Card::new(Clubs, King)
+            ]),
+            hand(vec![
+                Card::new(Clubs, Ace),
+                Card::new(Hearts, Jack),
+                Card::new(Clubs, Four)
+            ]),
+        ]
+    );
+}
+
+#[test]
+fn test_legal_bids() {
+    let mut game = Game::with_seed(4, GameSettings::default(), 1);
+    assert_eq!(
+        game.legal_bids(0),
+        vec![
+            None,
+            Some(Bid::Tricks(1)),
+            Some(Bid::Tricks(2)),
+            Some(Bid::Tricks(3)),
+            Some(Bid::Tricks(4)),
+            Some(Bid::Nap)
+        ]
+    );
+    assert!(game.legal_bids(1).is_empty());
+
+    assert!(game.bid(0, Some(Bid::Tricks(3))).is_ok());
+    assert_eq!(
+        game.legal_bids(1),
+        vec![None, Some(Bid::Tricks(4)), Some(Bid::Nap)]
+    );
+    assert!(game.bid(1, Some(Bid::Tricks(3))).is_err());
+    assert!(game.bid(1, Some(Bid::Tricks(0))).is_err());
+
+    // The dealer has to bid if everyone else passed
+    let mut game = Game::with_seed(4, GameSettings::default(), 1);
+    for player_id in 0..3 {
+        assert!(game.bid(player_id, None).is_ok());
+    }
+    assert_eq!(game.legal_bids(3).first(), Some(&Some(Bid::Tricks(1))));
+}
+
+#[test]
+fn test_named_bids() {
+    let mut game = Game::with_seed(5, GameSettings::default(), 1);
+    // A wellington can only be bid over a nap
+    assert!(game.bid(0, Some(Bid::Wellington)).is_err());
+    assert!(game.bid(0, Some(Bid::Tricks(3))).is_ok());
+    assert!(game.bid(1, Some(Bid::Blucher)).is_err());
+    // Bidding every round is a nap
+    assert!(game.bid(1, Some(Bid::Tricks(5))).is_ok());
+    assert_eq!(game.legal_bids(2), vec![None, Some(Bid::Wellington)]);
+    assert!(game.bid(2, Some(Bid::Wellington)).is_ok());
+    assert_eq!(game.legal_bids(3), vec![None, Some(Bid::Blucher)]);
+    assert!(game.bid(3, Some(Bid::Blucher)).is_ok());
+    assert_eq!(game.legal_bids(4), vec![None]);
+    assert!(game.bid(4, Some(Bid::Nap)).is_err());
+    assert!(game.bid(4, None).is_ok());
+
+    assert_eq!(
+        game.get_bids()[1..],
+        [
+            (1, Some(Bid::Nap)),
+            (2, Some(Bid::Wellington)),
+            (3, Some(Bid::Blucher)),
+            (4, None)
+        ]
+    );
+    assert_eq!(game.view_for(0).napoleon.unwrap().bid, Bid::Blucher);
+    assert_eq!(Bid::Blucher.multiplier(), 3 * Bid::Nap.multiplier());
+    assert_eq!("W".parse::<Bid>(), Ok(Bid::Wellington));
+    assert_eq!("4".parse::<Bid>(), Ok(Bid::Tricks(4)));
+}
+
+#[test]
+fn test_legal_cards_match_play_card() {
+    let mut game = Game::with_seed(4, GameSettings::default(), 7);
+    win_bidding(&mut game, Bid::Tricks(3));
+    assert!(game.legal_cards(0).is_empty());
+    assert!(game
+        .pick_allies(0, vec![Card::new(Hearts, Ace)], Trump::Suit(Spades))
+        .is_ok());
+
+    for _ in 0..game.get_settings().hand_size * 4 {
+        let player_id = (0..4)
+            .find(|&player_id| !game.legal_cards(player_id).is_empty())
+            .expect("Someone should be able to play");
+        let legal_cards = game.legal_cards(player_id);
+
+        for card in game.get_hands()[player_id] {
+            let accepted = game.clone().play_card(player_id, card.clone()).is_ok();
+            assert_eq!(accepted, legal_cards.contains(&card));
+        }
+
+        assert!(game.play_card(player_id, legal_cards[0].clone()).is_ok());
+    }
+}
+
+#[test]
+fn test_view_hides_unrevealed_allies() {
+    let mut game = Game::with_seed(4, GameSettings::default(), 3);
+    win_bidding(&mut game, Bid::Tricks(2));
+
+    // Call a card held by player 2 so that they become the only ally
+    let ally_card = game.get_hands()[2].iter().next().unwrap().clone();
+    assert!(game
+        .pick_allies(
+            0,
+            vec![ally_card.clone()],
+            Trump::Suit(ally_card.suit.clone())
+        )
+        .is_ok());
+
+    for player_id in 0..4 {
+        let view = game.view_for(player_id);
+        assert_eq!(view.hand, Deck::from(&game.get_hands()[player_id]));
+        assert_eq!(
+            view.bids,
+            vec![(0, Some(Bid::Tricks(2))), (1, None), (2, None), (3, None)]
+        );
+        assert_eq!(view.ally_cards, vec![ally_card.clone()]);
+        assert_eq!(view.allies, if player_id == 2 { vec![2] } else { vec![] });
+    }
+
+    // Play until the ally card has been played, after which everyone knows the ally
+    while game.get_hands()[2].contains(&ally_card) {
+        let player_id = game.view_for(0).current_player;
+        let card = if player_id == 2 && game.legal_cards(2).contains(&ally_card) {
+            ally_card.clone()
+        } else {
+            game.legal_cards(player_id)[0].clone()
+        };
+        let events = game.play_card(player_id, card.clone()).ok().unwrap();
+        assert_eq!(
+            matches!(
+                events.first(),
+                Some(PlayingEvent::AllyRevealed { player_id: 2, card: revealed }) if *revealed == ally_card
+            ),
+            card == ally_card
+        );
+    }
+
+    for player_id in 0..4 {
+        let view = game.view_for(player_id);
+        assert_eq!(view.allies, vec![2]);
+        assert_eq!(view.revealed_allies, vec![(2, ally_card.clone())]);
+    }
+}
+
+#[test]
+fn test_trick_history() {
+    let mut game = Game::with_seed(4, GameSettings::default(), 11);
+    start_playing(
+        &mut game,
+        Bid::Tricks(3),
+        Card::new(Hearts, Ace),
+        Trump::Suit(Clubs),
+    );
+
+    for _ in 0..game.get_settings().hand_size * 4 {
+        let player_id = game.view_for(0).current_player;
+        let card = game.legal_cards(player_id)[0].clone();
+        assert!(game.play_card(player_id, card).is_ok());
+    }
+
+    let tricks = game.get_tricks();
+    assert_eq!(tricks.len(), game.get_settings().hand_size as usize);
+    assert_eq!(tricks[0].leader, 0);
+
+    for (i, trick) in tricks.iter().enumerate() {
+        let players: Vec<usize> = trick
+            .cards
+            .iter()
+            .map(|(player_id, _)| *player_id)
+            .collect();
+        let expected: Vec<usize> = (0..4).map(|offset| (trick.leader + offset) % 4).collect();
+        assert_eq!(players, expected);
+        assert_eq!(trick.led_suit, trick.cards[0].1.suit);
+
+        if let Some(next) = tricks.get(i + 1) {
+            assert_eq!(next.leader, trick.winner);
+        }
+    }
+
+    for player_id in 0..4 {
+        let won = tricks
+            .iter()
+            .filter(|trick| trick.winner == player_id)
+            .count();
+        assert_eq!(game.get_score()[player_id] as usize, won);
+    }
+}
+
+#[test]
+fn test_rule_variants() {
+    use game::rules::{FirstLead, FollowSuit, Ranking};
+
+    let rules = RuleSet {
+        first_lead: FirstLead::Any,
+        follow_suit: FollowSuit::TrumpAnytime,
+        ranking: Ranking::AceLow,
+        ..RuleSet::default()
+    };
+    let mut game = Game::with_seed(
+        4,
+        GameSettings {
+            rules: rules.clone(),
+            ..GameSettings::default()
+        },
+        13,
+    );
+    start_playing(
+        &mut game,
+        Bid::Tricks(3),
+        Card::new(Hearts, Ace),
+        Trump::Suit(Clubs),
+    );
+
+    // The napoleon may lead anything since trumps aren't forced on the first round
+    assert_eq!(
+        game.legal_cards(0).len(),
+        game.get_settings().hand_size as usize
+    );
+
+    let hand: CardSet = vec![
+        Card::new(Hearts, Two),
+        Card::new(Clubs, Two),
+        Card::new(Spades, Two),
+    ]
+    .into_iter()
+    .collect();
+    let led = Some(Hearts);
+    assert!(rules.can_play(&hand, &Card::new(Clubs, Two), &led, &Trump::Suit(Clubs)));
+    assert!(!rules.can_play(&hand, &Card::new(Spades, Two), &led, &Trump::Suit(Clubs)));
+
+    assert_eq!(
+        rules.winning_card(
+            &[Card::new(Hearts, Ace), Card::new(Hearts, King)],
+            &Trump::Suit(Clubs)
+        ),
+        1
+    );
+    assert_eq!(
+        RuleSet::default().winning_card(
+            &[Card::new(Hearts, Ace), Card::new(Hearts, King)],
+            &Trump::Suit(Clubs)
+        ),
+        0
+    );
+}
+
+#[test]
+fn test_misere() {
+    let misere_settings = GameSettings {
+        misere: Some(2),
+        ..GameSettings::default()
+    };
+    let mut game = Game::with_seed(4, misere_settings.clone(), 17);
+    assert_eq!(
+        game.legal_bids(0),
+        vec![
+            None,
+            Some(Bid::Tricks(1)),
+            Some(Bid::Tricks(2)),
+            Some(Bid::Misere),
+            Some(Bid::Tricks(3)),
+            Some(Bid::Tricks(4)),
+            Some(Bid::Nap)
+        ]
+    );
+    assert!(game.bid(0, Some(Bid::Tricks(2))).is_ok());
+    assert!(game.bid(1, Some(Bid::Misere)).is_ok());
+    assert!(game.bid(2, Some(Bid::Tricks(2))).is_err());
+    assert!(game.bid(2, None).is_ok());
+    assert!(game.bid(3, None).is_ok());
+    assert!(game
+        .pick_allies(1, vec![Card::new(Spades, Ace)], Trump::Suit(Hearts))
+        .is_ok());
+
+    let mut last_event = None;
+    for _ in 0..game.get_settings().hand_size * 4 {
+        let player_id = game.view_for(0).current_player;
+        let card = game.legal_cards(player_id)[0].clone();
+        last_event = game
+            .play_card(player_id, card)
+            .ok()
+            .and_then(|mut events| events.pop());
+    }
+
+    match last_event {
+        Some(PlayingEvent::GameEnded {
+            combined_napoleon_score,
+            contract_made,
+            ..
+        }) => assert_eq!(contract_made, combined_napoleon_score == 0),
+        _ => panic!("The game should have ended"),
+    }
+
+    // Misère can't be bid unless it is enabled
+    let mut game = Game::with_seed(4, GameSettings::default(), 17);
+    assert!(game.bid(0, Some(Bid::Misere)).is_err());
+}
+
+#[test]
+fn test_auction() {
+    let auction_settings = GameSettings {
+        auction: true,
+        ..GameSettings::default()
+    };
+    let mut game = Game::with_seed(3, auction_settings.clone(), 19);
+    assert!(game.bid(0, Some(Bid::Tricks(2))).is_ok());
+    assert!(game.bid(1, Some(Bid::Tricks(3))).is_ok());
+    assert!(game.bid(2, None).is_ok());
+
+    // Bidding comes back around to the first player who can respond to the higher bid
+    assert_eq!(
+        game.legal_bids(0),
+        vec![None, Some(Bid::Tricks(4)), Some(Bid::Nap)]
+    );
+    match game.bid(0, Some(Bid::Tricks(4))) {
+        Ok(BiddingEvent::NextBidder { player_id: 1 }) => {}
+        _ => panic!("Player 1 should bid next"),
+    }
+    // Player 2 passed so is skipped
+    match game.bid(1, None) {
+        Ok(BiddingEvent::BiddingFinished { napoleon, .. }) => {
+            assert_eq!(napoleon.player_id, 0);
+            assert_eq!(napoleon.bid, Bid::Tricks(4));
+        }
+        _ => panic!("Bidding should have finished"),
+    }
+
+    let mut game = Game::with_seed(3, auction_settings.clone(), 19);
+    assert!(game.bid(0, None).is_ok());
+    assert!(game.bid(1, None).is_ok());
+    // Passing would end the auction with no bids so it isn't offered
+    assert_eq!(game.legal_bids(2).first(), Some(&Some(Bid::Tricks(1))));
+    assert!(matches!(game.bid(2, None), Err(BiddingError::NoBids)));
+
+    let mut game = Game::with_seed(3, auction_settings, 19);
+    assert!(game.bid(0, None).is_ok());
+    assert!(game.bid(1, None).is_ok());
+    // Even though everyone else passed the last player can still bid
+    assert!(game.bid(2, Some(Bid::Tricks(1))).is_ok());
+}
+
+#[test]
+fn test_dealer() {
+    let mut game = Game::with_dealer(4, GameSettings::default(), 23, 1);
+    assert_eq!(game.get_dealer(), 1);
+    assert_eq!(game.first_bidder(), 2);
+    assert!(game.legal_bids(0).is_empty());
+
+    assert!(game.bid(2, None).is_ok());
+    assert!(game.bid(3, Some(Bid::Tricks(2))).is_ok());
+    assert!(game.bid(0, None).is_ok());
+    // The dealer bids last
+    match game.bid(1, None) {
+        Ok(BiddingEvent::BiddingFinished { napoleon, .. }) => assert_eq!(napoleon.player_id, 3),
+        _ => panic!("Bidding should finish with the dealer"),
+    }
+}
+
+#[test]
+fn test_kitty() {
+    let kitty_settings = GameSettings {
+        kitty_size: 2,
+        ..GameSettings::default()
+    };
+    let mut game = Game::with_seed(4, kitty_settings, 29);
+    let kitty = game.get_kitty().clone();
+    assert_eq!(kitty.len(), 2);
+
+    win_bidding(&mut game, Bid::Tricks(3));
+
+    // The napoleon has to discard before picking allies
+    assert!(game
+        .pick_allies(0, vec![Card::new(Hearts, Ace)], Trump::Suit(Clubs))
+        .is_err());
+    let hand = game.get_hands()[0];
+    assert_eq!(hand.len(), 7);
+    assert!(kitty.iter().all(|card| hand.contains(card)));
+
+    let discard: Vec<Card> = hand.iter().take(2).collect();
+    assert!(matches!(
+        game.discard(1, discard.clone()),
+        Err(ExchangeError::NotCurrentPlayer { current_player: 0 })
+    ));
+    assert!(matches!(
+        game.discard(0, discard[..1].to_vec()),
+        Err(ExchangeError::IncorrectDiscardCount {
+            expected: 2,
+            received: 1
+        })
+    ));
+    assert!(matches!(
+        game.discard(0, vec![discard[0].clone(), discard[0].clone()]),
+        Err(ExchangeError::CardNotInHand)
+    ));
+    assert!(game.discard(0, discard.clone()).is_ok());
+    assert_eq!(game.get_hands()[0].len(), 5);
+    assert!(!game.get_hands()[0].contains(&discard[0]));
+
+    assert!(game
+        .pick_allies(0, vec![Card::new(Hearts, Ace)], Trump::Suit(Clubs))
+        .is_ok());
+}
+
+#[test]
+fn test_fixed_partnerships() {
+    let team_settings = GameSettings {
+        teams: Some(vec![0, 1, 0, 1]),
+        ..GameSettings::default()
+    };
+    let mut game = Game::with_seed(4, team_settings, 31);
+    assert!(game.bid(0, None).is_ok());
+    assert!(game.bid(1, Some(Bid::Tricks(3))).is_ok());
+    assert!(game.bid(2, None).is_ok());
+    assert!(game.bid(3, None).is_ok());
+
+    assert!(game
+        .pick_allies(1, vec![Card::new(Hearts, Ace)], Trump::Suit(Clubs))
+        .is_err());
+    match game.pick_allies(1, vec![], Trump::Suit(Clubs)) {
+        Ok(PostBiddingEvent::AlliesChosen { allies, .. }) => assert_eq!(allies, vec![3]),
+        _ => panic!("The napoleon's partner should be their ally"),
+    }
+
+    // Partnerships are public knowledge
+    for player_id in 0..4 {
+        assert_eq!(game.view_for(player_id).allies, vec![3]);
+    }
+}
+
+#[test]
+fn test_scoring() {
+    let settings = GameSettings::default();
+    let flat = Scoring::default();
+    let per_bid = Scoring {
+        scheme: ScoringScheme::PerBid,
+        stakes: true,
+        solo_bonus: 2,
+    };
+    let per_overtrick = Scoring {
+        scheme: ScoringScheme::PerOvertrick,
+        stakes: false,
+        ..Scoring::default()
+    };
+
+    // Winning more rounds than were bid still makes the contract
+    assert!(Bid::Tricks(3).is_made(4, settings.hand_size));
+    assert_eq!(
+        flat.score(&Bid::Tricks(3), 4, false, &settings),
+        Score {
+            napoleon_side: 15,
+            opponents: -10
+        }
+    );
+    assert_eq!(
+        per_overtrick.score(&Bid::Tricks(3), 4, false, &settings),
+        Score {
+            napoleon_side: 4,
+            opponents: -3
+        }
+    );
+    assert_eq!(
+        per_bid.score(&Bid::Tricks(3), 2, false, &settings),
+        Score {
+            napoleon_side: -3,
+            opponents: 3
+        }
+    );
+    // A nap is played for double stakes
+    assert_eq!(
+        per_bid.score(&Bid::Nap, 5, false, &settings),
+        Score {
+            napoleon_side: 10,
+            opponents: -10
+        }
+    );
+
+    // Going solo adds the bonus before the stakes are applied, but only when the contract is made
+    assert_eq!(
+        per_bid.score(&Bid::Nap, 5, true, &settings).napoleon_side,
+        14
+    );
+    assert_eq!(
+        per_bid.score(&Bid::Nap, 4, true, &settings),
+        Score {
+            napoleon_side: -10,
+            opponents: 10
+        }
+    );
+
+    let score = flat.score(&Bid::Tricks(3), 1, false, &settings);
+    assert_eq!(score.deltas(4, 2, &[0]), vec![-10, 15, -10, 15]);
+
+    // Points saturate instead of overflowing or wrapping around
+    let huge_bonus = Scoring {
+        stakes: true,
+        solo_bonus: i32::MAX,
+        ..Scoring::default()
+    };
+    assert_eq!(
+        huge_bonus
+            .score(&Bid::Nap, 5, true, &settings)
+            .napoleon_side,
+        i32::MAX
+    );
+    let huge_misere = GameSettings {
+        misere: Some(u32::MAX),
+        ..settings.clone()
+    };
+    assert_eq!(
+        per_bid
+            .score(&Bid::Misere, 0, false, &huge_misere)
+            .napoleon_side,
+        i32::MAX
+    );
+}
+
+#[test]
+fn test_multiple_packs() {
+    use game::rules::Duplicates;
+
+    let settings = GameSettings {
+        ally_count: 1,
+        hand_size: 10,
+        pack_count: 2,
+        ..GameSettings::default()
+    };
+    let mut game = Game::with_seed(8, settings.clone(), 3);
+    assert_eq!(
+        game.get_hands()
+            .iter()
+            .map(|hand| hand.count(&Card::new(Spades, Ace)))
+            .sum::<usize>()
+            + game
+                .get_kitty()
+                .iter()
+                .filter(|card| **card == Card::new(Spades, Ace))
+                .count(),
+        2
+    );
+
+    for player_id in 0..8 {
+        let bid = if player_id == 0 {
+            Some(Bid::Tricks(6))
+        } else {
+            None
+        };
+        assert!(game.bid(player_id, bid).is_ok());
+    }
+
+    assert!(matches!(
+        game.pick_allies(
+            0,
+            vec![Card::new(Spades, Ace), Card::new(Spades, Ace)],
+            Trump::Suit(Hearts)
+        ),
+        Err(PostBiddingError::IncorrectAllyCount { .. })
+    ));
+
+    let mut settings = settings;
+    settings.ally_count = 2;
+    let mut two_allies = Game::with_seed(8, settings, 3);
+    for player_id in 0..8 {
+        let bid = if player_id == 0 {
+            Some(Bid::Tricks(6))
+        } else {
+            None
+        };
+        assert!(two_allies.bid(player_id, bid).is_ok());
+    }
+    assert!(matches!(
+        two_allies.pick_allies(
+            0,
+            vec![Card::new(Spades, Ace), Card::new(Spades, Ace)],
+            Trump::Suit(Hearts)
+        ),
+        Err(PostBiddingError::DuplicateAllyCard)
+    ));
+
+    // Everyone holding a copy of the called card is an ally
+    let holders: Vec<usize> = (1..8)
+        .filter(|id| game.get_hands()[*id].contains(&Card::new(Spades, Ace)))
+        .collect();
+    match game.pick_allies(0, vec![Card::new(Spades, Ace)], Trump::Suit(Hearts)) {
+        Ok(PostBiddingEvent::AlliesChosen { allies, .. }) => assert_eq!(allies, holders),
+        _ => panic!("Picking allies should have succeeded"),
+    }
+
+    let trick = [
+        Card::new(Hearts, Ace),
+        Card::new(Hearts, King),
+        Card::new(Hearts, Ace),
+    ];
+    assert_eq!(
+        RuleSet::default().winning_card(&trick, &Trump::Suit(Clubs)),
+        0
+    );
+    let rules = RuleSet {
+        duplicates: Duplicates::LastPlayedWins,
+        ..RuleSet::default()
+    };
+    assert_eq!(rules.winning_card(&trick, &Trump::Suit(Clubs)), 2);
+}
+
+#[test]
+fn test_special_cards() {
+    let rules = RuleSet {
+        special_cards: vec![Card::joker(), Card::new(Spades, Ace)],
+        ..RuleSet::default()
+    };
+
+    let trick = [
+        Card::new(Hearts, Two),
+        Card::new(Spades, Ace),
+        Card::new(Clubs, Ace),
+    ];
+    assert_eq!(rules.winning_card(&trick, &Trump::Suit(Clubs)), 1);
+    assert_eq!(
+        RuleSet::default().winning_card(&trick, &Trump::Suit(Clubs)),
+        2
+    );
+
+    let trick = [
+        Card::new(Spades, Ace),
+        Card::new(Hearts, Two),
+        Card::joker(),
+    ];
+    assert_eq!(rules.winning_card(&trick, &Trump::Suit(Clubs)), 2);
+
+    // Special cards can be played even when the player could follow suit
+    let hand: CardSet = vec![
+        Card::new(Hearts, Two),
+        Card::new(Spades, Ace),
+        Card::joker(),
+    ]
+    .into_iter()
+    .collect();
+    let led = Some(Hearts);
+    assert!(rules.can_play(&hand, &Card::new(Spades, Ace), &led, &Trump::Suit(Clubs)));
+    assert!(rules.can_play(&hand, &Card::joker(), &led, &Trump::Suit(Clubs)));
+    assert!(!RuleSet::default().can_play(
+        &hand,
+        &Card::new(Spades, Ace),
+        &led,
+        &Trump::Suit(Clubs)
+    ));
+}
+
+#[test]
+fn test_joker_lead() {
+    let settings = GameSettings {
+        hand_size: 2,
+        jokers: 2,
+        rules: RuleSet {
+            first_lead: game::rules::FirstLead::Any,
+            ..RuleSet::default()
+        },
+        ..GameSettings::default()
+    };
+    let deal = "3:XX,2S 2H,XX 3H,4C 5H,6C";
+    let mut game = Game::from_deal(settings, deal.parse().unwrap()).unwrap();
+    start_playing(
+        &mut game,
+        Bid::Tricks(1),
+        Card::new(Spades, Ace),
+        Trump::Suit(Clubs),
+    );
+
+    // A joker doesn't call for a suit, the next card played does
+    match game.play_card(0, Card::joker()).unwrap().pop() {
+        Some(PlayingEvent::NextPlayer { required_suit, .. }) => assert_eq!(required_suit, None),
+        _ => panic!("The next player should play"),
+    }
+    assert_eq!(game.legal_cards(1).len(), 2);
+    assert!(game.play_card(1, Card::new(Hearts, Two)).is_ok());
+    assert_eq!(game.legal_cards(2), vec![Card::new(Hearts, Three)]);
+    assert!(game.play_card(2, Card::new(Hearts, Three)).is_ok());
+    assert!(game.play_card(3, Card::new(Hearts, Five)).is_ok());
+
+    let trick = &game.get_tricks()[0];
+    assert_eq!(trick.led_suit, Hearts);
+    assert_eq!(trick.winner, 3);
+}
+
+#[test]
+fn test_deal_notation() {
+    let game = Game::with_seed(4, GameSettings::default(), 9);
+    let deal = game.get_deal();
+    let notation = deal.to_string();
+    assert_eq!(notation.parse::<Deal>(), Ok(deal.clone()));
+
+    let restored = Game::from_deal(GameSettings::default(), notation.parse().unwrap()).unwrap();
+    assert_eq!(restored.get_hands(), game.get_hands());
+    assert_eq!(restored.get_dealer(), 3);
+
+    let deal: Deal = "0:2H,3H,4H,5H,6H 2S,3S,4S,5S,6S 2C,3C,4C,5C,6C 2D,3D,4D,5D,6D"
+        .parse()
+        .unwrap();
+    assert_eq!(deal.hands[1].to_string(), "2S,3S,4S,5S,6S");
+    assert!(Game::from_deal(GameSettings::default(), deal).is_ok());
+
+    // Two copies of the same card can't come from one pack
+    let deal: Deal = "0:2H,2H,4H,5H,6H 2S,3S,4S,5S,6S".parse().unwrap();
+    assert!(matches!(
+        Game::from_deal(GameSettings::default(), deal),
+        Err(DealError::CardsNotInDeck)
+    ));
+    assert!(matches!(
+        "2H,3H".parse::<Deal>(),
+        Err(ParseDealError::MissingDealer)
+    ));
+}
+
+#[test]
+fn test_going_solo() {
+    let deal = "3:2H,3H,4H,5H,6H 2S,3S,4S,5S,6S 2C,3C,4C,5C,6C 2D,3D,4D,5D,6D";
+    let start = |settings: GameSettings| {
+        let mut game = Game::from_deal(settings, deal.parse().unwrap()).unwrap();
+        win_bidding(&mut game, Bid::Tricks(3));
+        game
+    };
+
+    // Calling a card that someone else holds is the normal game
+    match start(GameSettings::default()).pick_allies(
+        0,
+        vec![Card::new(Spades, Two)],
+        Trump::Suit(Hearts),
+    ) {
+        Ok(PostBiddingEvent::AlliesChosen { allies, solo }) => {
+            assert_eq!(allies, vec![1]);
+            assert!(!solo);
+        }
+        _ => panic!("Player 1 should be the ally"),
+    }
+
+    // Calling a card that wasn't dealt or is in the napoleon's own hand goes solo
+    for card in [Card::new(Spades, Ace), Card::new(Hearts, Two)] {
+        match start(GameSettings::default()).pick_allies(0, vec![card], Trump::Suit(Hearts)) {
+            Ok(PostBiddingEvent::AlliesChosen { allies, solo }) => {
+                assert!(allies.is_empty());
+                assert!(solo);
+            }
+            _ => panic!("The napoleon should have gone solo"),
+        }
+    }
+
+    let no_self_call = GameSettings {
+        allow_self_call: false,
+        ..GameSettings::default()
+    };
+    let mut game = start(no_self_call);
+    assert_eq!(
+        game.pick_allies(0, vec![Card::new(Hearts, Two)], Trump::Suit(Hearts))
+            .err()
+            .unwrap(),
+        PostBiddingError::CalledOwnCard
+    );
+    assert!(game
+        .pick_allies(0, vec![Card::new(Spades, Ace)], Trump::Suit(Hearts))
+        .is_ok());
+}
+
+#[test]
+fn test_claim() {
+    let deal = "3:AS,KS,QS,JS,TS 2H,3H,4H,5H,6H 2C,3C,4C,5C,6C 2D,3D,4D,5D,6D";
+    let mut game = Game::from_deal(GameSettings::default(), deal.parse().unwrap()).unwrap();
+    start_playing(
+        &mut game,
+        Bid::Tricks(3),
+        Card::new(Hearts, Ace),
+        Trump::Suit(Spades),
+    );
+
+    // The napoleon holds every top spade so nobody can stop them winning the rest
+    assert_eq!(game.check_claim(0, &ClaimKind::AllTricks), Some(true));
+    assert_eq!(game.check_claim(1, &ClaimKind::AllTricks), Some(false));
+    assert_eq!(game.check_claim(0, &ClaimKind::Concede), Some(false));
+
+    // With whole suits in every hand there are far too many ways to play out the rest to check
+    let suit = |suit: char| {
+        "AKQJT98765432"
+            .chars()
+            .map(|number| format!("{}{}", number, suit))
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let full_deal = format!("3:{} {} {} {}", suit('S'), suit('H'), suit('C'), suit('D'));
+    let full_settings = GameSettings {
+        hand_size: 13,
+        ..GameSettings::default()
+    };
+    let mut full_game = Game::from_deal(full_settings, full_deal.parse().unwrap()).unwrap();
+    start_playing(
+        &mut full_game,
+        Bid::Tricks(3),
+        Card::new(Hearts, Ace),
+        Trump::Suit(Spades),
+    );
+    assert_eq!(full_game.check_claim(0, &ClaimKind::AllTricks), None);
+    assert_eq!(
+        game.claim(1, ClaimKind::Concede).err().unwrap(),
+        ClaimError::NotNapoleon
+    );
+
+    // Play stops until the claim has been answered, a rejection carries on from where it was
+    assert!(game.claim(0, ClaimKind::AllTricks).is_ok());
+    assert_eq!(
+        game.play_card(0, Card::new(Spades, Ace)).err().unwrap(),
+        PlayingError::ClaimPending
+    );
+    assert!(matches!(
+        game.respond_to_claim(2, false),
+        Ok(ClaimEvent::Rejected { player_id: 2 })
+    ));
+    assert!(game.play_card(0, Card::new(Spades, Ace)).is_ok());
+
+    assert!(game.claim(0, ClaimKind::AllTricks).is_ok());
+    assert_eq!(game.view_for(1).claim.map(|claim| claim.player_id), Some(0));
+    assert!(matches!(
+        game.respond_to_claim(1, true),
+        Ok(ClaimEvent::AwaitingResponses { players }) if players == vec![2, 3]
+    ));
+    assert_eq!(
+        game.respond_to_claim(1, true).err().unwrap(),
+        ClaimError::AlreadyResponded
+    );
+    assert_eq!(
+        game.respond_to_claim(4, true).err().unwrap(),
+        ClaimError::NotAtTable
+    );
+    assert!(game.respond_to_claim(2, true).is_ok());
+    match game.respond_to_claim(3, true) {
+        Ok(ClaimEvent::Settled(PlayingEvent::GameEnded {
+            combined_napoleon_score,
+            contract_made,
+            ..
+        })) => {
+            assert_eq!(combined_napoleon_score, 5);
+            assert!(contract_made);
+        }
+        _ => panic!("The claim should have ended the game"),
+    }
+    // The claimed rounds weren't won by anyone so they're only counted for the napoleon's side
+    assert_eq!(game.get_score(), &[0, 0, 0, 0]);
+    assert_eq!(
+        game.view_for(2).settled_rounds,
+        SettledRounds {
+            napoleon_side: 5,
+            opponents: 0
+        }
+    );
+    assert!(game.get_hands().iter().all(|hand| hand.is_empty()));
+
+    // A concession gives every remaining round to the napoleon's opponents
+    let mut game = Game::from_deal(GameSettings::default(), deal.parse().unwrap()).unwrap();
+    start_playing(
+        &mut game,
+        Bid::Tricks(3),
+        Card::new(Hearts, Ace),
+        Trump::Suit(Spades),
+    );
+    assert!(game.play_card(0, Card::new(Spades, Ace)).is_ok());
+    assert_eq!(
+        game.claim(4, ClaimKind::AllTricks).err().unwrap(),
+        ClaimError::NotAtTable
+    );
+    assert!(game.claim(0, ClaimKind::Concede).is_ok());
+    for player_id in 1..3 {
+        assert!(game.respond_to_claim(player_id, true).is_ok());
+    }
+    match game.respond_to_claim(3, true) {
+        Ok(ClaimEvent::Settled(PlayingEvent::GameEnded {
+            combined_napoleon_score,
+            contract_made,
+            ..
+        })) => {
+            assert_eq!(combined_napoleon_score, 0);
+            assert!(!contract_made);
+        }
+        _ => panic!("The concession should have ended the game"),
+    }
+    assert_eq!(game.get_score(), &[0, 0, 0, 0]);
+    assert_eq!(
+        game.get_settled_rounds(),
+        &SettledRounds {
+            napoleon_side: 0,
+            opponents: 5
+        }
+    );
+}
+
+#[test]
+fn test_no_trump() {
+    assert_eq!("N".parse::<Trump>(), Ok(Trump::NoTrump));
+    assert_eq!("H".parse::<Trump>(), Ok(Trump::Suit(Hearts)));
+    assert_eq!(Trump::NoTrump.to_string(), "N");
+    assert_eq!("3NT".parse::<Bid>(), Ok(Bid::NoTrump(3)));
+    assert_eq!(Bid::NoTrump(3).to_string(), "3NT");
+
+    // With no trumps the highest card of the led suit wins
+    let trick = [Card::new(Hearts, Two), Card::new(Spades, Ace)];
+    assert_eq!(RuleSet::default().winning_card(&trick, &Trump::NoTrump), 0);
+
+    // No-trump bids have to be turned on
+    let mut game = Game::with_seed(4, GameSettings::default(), 0);
+    assert_eq!(
+        game.bid(0, Some(Bid::NoTrump(3))).err().unwrap(),
+        BiddingError::BidNotAllowed
+    );
+
+    let equal = GameSettings {
+        no_trump: Some(NoTrumpRanking::Equal),
+        ..GameSettings::default()
+    };
+    let mut game = Game::with_seed(4, equal, 0);
+    assert!(game.bid(0, Some(Bid::Tricks(3))).is_ok());
+    assert_eq!(
+        game.bid(1, Some(Bid::NoTrump(3))).err().unwrap(),
+        BiddingError::BidTooLow {
+            min: Bid::Tricks(4)
+        }
+    );
+
+    let higher = GameSettings {
+        no_trump: Some(NoTrumpRanking::Higher),
+        ..GameSettings::default()
+    };
+    let mut game = Game::with_seed(4, higher, 0);
+    // Even though a no-trump bid ranks higher, a contract has to win at least one round
+    for bid in [Bid::NoTrump(0), Bid::Tricks(0)] {
+        assert_eq!(
+            game.bid(0, Some(bid)).err().unwrap(),
+            BiddingError::BidTooLow {
+                min: Bid::Tricks(1)
+            }
+        );
+    }
+    assert!(game.bid(0, Some(Bid::Tricks(3))).is_ok());
+    assert_eq!(
+        game.legal_bids(1)[..3],
+        [None, Some(Bid::NoTrump(3)), Some(Bid::Tricks(4))]
+    );
+    assert!(game.bid(1, Some(Bid::NoTrump(3))).is_ok());
+    assert!(game.bid(2, None).is_ok());
+    assert!(game.bid(3, None).is_ok());
+
+    // A no-trump contract has to be played with no trumps, and then any card can be led
+    let ally_card = Card::new(Hearts, Ace);
+    assert_eq!(
+        game.pick_allies(1, vec![ally_card.clone()], Trump::Suit(Hearts))
+            .err()
+            .unwrap(),
+        PostBiddingError::NoTrumpBid
+    );
+    assert!(game.pick_allies(1, vec![ally_card], Trump::NoTrump).is_ok());
+    assert_eq!(game.view_for(0).trump, Some(Trump::NoTrump));
+    assert_eq!(game.legal_cards(1).len(), 5);
+
+    // And only a no-trump contract can be
+    let mut game = Game::with_seed(4, GameSettings::default(), 0);
+    win_bidding(&mut game, Bid::Tricks(3));
+    assert_eq!(
+        game.pick_allies(0, vec![Card::new(Hearts, Ace)], Trump::NoTrump)
+            .err()
+            .unwrap(),
+        PostBiddingError::NoTrumpNotBid
+    );
+}
+
+#[test]
+fn test_errors() {
+    let mut game = Game::with_seed(4, GameSettings::default(), 0);
+    assert!(game.bid(0, Some(Bid::Tricks(3))).is_ok());
+
+    let error = game.bid(1, Some(Bid::Tricks(2))).err().unwrap();
+    assert_eq!(error.code(), "bid_too_low");
+    assert_eq!(error.to_string(), "the lowest bid allowed is 4");
+
+    let error = game::Error::from(game.bid(3, None).err().unwrap());
+    assert_eq!(error.code(), "not_current_player");
+    assert!(std::error::Error::source(&error).is_some());
+
+    let error: game::Error = "1H".parse::<Card>().unwrap_err().into();
+    assert_eq!(error.code(), "invalid_number_char");
+    assert_eq!(error.to_string(), "'1' is not a card number");
+}
+
+#[test]
+fn test_settings_builder() {
+    for players in 2..=12 {
+        let settings = GameSettings::builder().build(players).unwrap();
+        assert!(settings.hand_size as usize * players <= 52);
+        assert!(settings.ally_count < players - 1);
+        // The settings must be good enough to deal a game with
+        Game::with_seed(players, settings, 0);
+    }
+
+    let settings = GameSettings::builder().build(4).unwrap();
+    assert_eq!((settings.hand_size, settings.ally_count), (5, 1));
+    assert_eq!(GameSettings::builder().build(12).unwrap().hand_size, 4);
+    assert_eq!(
+        GameSettings::builder()
+            .pack_count(2)
+            .build(12)
+            .unwrap()
+            .hand_size,
+        5
+    );
+
+    assert_eq!(
+        GameSettings::builder().hand_size(14).build(4),
+        Err(SettingsError::NotEnoughCards {
+            needed: 56,
+            available: 52
+        })
+    );
+    assert_eq!(
+        GameSettings::builder().ally_count(3).build(4),
+        Err(SettingsError::TooManyAllies { max: 2 })
+    );
+    assert_eq!(
+        GameSettings::builder().build(1),
+        Err(SettingsError::TooFewPlayers { min: 2 })
+    );
+    assert_eq!(
+        GameSettings::builder().pack_count(usize::MAX).build(4),
+        Err(SettingsError::TooManyPacks { max: 8 })
+    );
+    assert_eq!(
+        GameSettings::builder().jokers(usize::MAX).build(4),
+        Err(SettingsError::TooManyJokers { max: 4 })
+    );
+    assert_eq!(
+        GameSettings::builder()
+            .hand_size(u32::MAX)
+            .build(usize::MAX),
+        Err(SettingsError::TooManyCards)
+    );
+    assert_eq!(
+        GameSettings::builder().teams(Some(vec![1; 4])).build(4),
+        Err(SettingsError::InvalidTeams)
+    );
+    assert_eq!(
+        GameSettings::builder().misere(Some(5)).build(4),
+        Err(SettingsError::InvalidMisere { max: 4 })
+    );
+    assert_eq!(
+        GameSettings::builder()
+            .scoring(Scoring {
+                solo_bonus: i32::MIN,
+                ..Scoring::default()
+            })
+            .build(4),
+        Err(SettingsError::SoloBonusTooLarge { max: 1000 })
+    );
+
+    // Missing fields in JSON settings are filled in automatically
+    let builder: GameSettingsBuilder = serde_json::from_str(r#"{"hand_size":3}"#).unwrap();
+    assert_eq!(builder.build(6).unwrap().hand_size, 3);
+}
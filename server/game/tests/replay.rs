@@ -0,0 +1,102 @@
+use game::deck::{Card, Number::*, Suit::*};
+use game::{Action, Bid, DealError, Game, GameSettings, ReplayError, SettingsError, Trump};
+
+fn played_game(seed: u64) -> Game {
+    let mut game = Game::with_seed(4, GameSettings::default(), seed);
+    assert!(game.bid(0, None).is_ok());
+    assert!(game.bid(1, Some(Bid::Tricks(2))).is_ok());
+    assert!(game.bid(2, None).is_ok());
+    assert!(game.bid(3, None).is_ok());
+    assert!(game
+        .pick_allies(1, vec![Card::new(Spades, King)], Trump::Suit(Diamonds))
+        .is_ok());
+
+    for _ in 0..game.get_settings().hand_size * 4 {
+        let player_id = game.view_for(0).current_player;
+        let card = game.legal_cards(player_id).pop().unwrap();
+        assert!(game.play_card(player_id, card).is_ok());
+    }
+
+    game
+}
+
+#[test]
+fn test_replay_matches_original() {
+    let game = played_game(5);
+    let log = game.get_log();
+    assert_eq!(
+        log[0],
+        Action::Deal {
+            players: 4,
+            dealer: 3
+        }
+    );
+    assert_eq!(
+        log.len(),
+        1 + 4 + 1 + game.get_settings().hand_size as usize * 4
+    );
+
+    let json = serde_json::to_string(log).unwrap();
+    let log: Vec<Action> = serde_json::from_str(&json).unwrap();
+
+    match Game::replay(game.get_seed(), GameSettings::default(), &log) {
+        Ok(replayed) => assert_eq!(replayed, game),
+        Err(_) => panic!("Replaying a valid log failed"),
+    }
+}
+
+#[test]
+fn test_replay_fails_on_divergence() {
+    let game = played_game(5);
+    let mut log = game.get_log().to_vec();
+
+    // Replace the first card played with the one played after it (which the first player
+    // can't have had in their hand)
+    let index = log
+        .iter()
+        .position(|action| matches!(action, Action::PlayCard { .. }))
+        .unwrap();
+    log[index] = Action::PlayCard {
+        player_id: 1,
+        card: game.get_tricks()[0].cards[1].1.clone(),
+    };
+
+    match Game::replay(game.get_seed(), GameSettings::default(), &log) {
+        Err(ReplayError::Playing { index: failed, .. }) => assert_eq!(failed, index),
+        _ => panic!("Replay should have failed on the changed card"),
+    }
+
+    match Game::replay(game.get_seed(), GameSettings::default(), &log[1..]) {
+        Err(ReplayError::InvalidDeal { index: 0 }) => {}
+        _ => panic!("Replay should require a deal first"),
+    }
+}
+
+#[test]
+fn test_replay_checks_the_deal() {
+    let deal = |players, dealer| [Action::Deal { players, dealer }];
+
+    match Game::replay(0, GameSettings::default(), &deal(0, 0)) {
+        Err(ReplayError::Settings {
+            index: 0,
+            error: SettingsError::TooFewPlayers { .. },
+        }) => {}
+        _ => panic!("Replay should reject a deal with no players"),
+    }
+
+    match Game::replay(0, GameSettings::default(), &deal(20, 0)) {
+        Err(ReplayError::Settings {
+            index: 0,
+            error: SettingsError::NotEnoughCards { .. },
+        }) => {}
+        _ => panic!("Replay should reject a deal with too few cards"),
+    }
+
+    match Game::replay(0, GameSettings::default(), &deal(4, 4)) {
+        Err(ReplayError::Deal {
+            index: 0,
+            error: DealError::InvalidDealer,
+        }) => {}
+        _ => panic!("Replay should reject a dealer who isn't playing"),
+    }
+}
@@ -0,0 +1,34 @@
+use game::{Bid, Game, GameSettings, Snapshot, SnapshotError, SNAPSHOT_VERSION};
+
+#[test]
+fn test_snapshot_round_trip() {
+    let mut game = Game::new(4, GameSettings::default());
+    assert!(game.bid(0, Some(Bid::Tricks(3))).is_ok());
+    assert!(game.bid(1, None).is_ok());
+
+    let json = serde_json::to_string(&game.snapshot()).unwrap();
+    let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+    assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+
+    let mut restored = Game::restore(snapshot).unwrap();
+    assert_eq!(restored, game);
+
+    // The restored game must carry on exactly where the original left off
+    assert!(restored.bid(2, Some(Bid::Tricks(4))).is_ok());
+    assert!(game.bid(2, Some(Bid::Tricks(4))).is_ok());
+    assert_eq!(restored, game);
+}
+
+#[test]
+fn test_snapshot_unsupported_version() {
+    let mut snapshot = Game::new(4, GameSettings::default()).snapshot();
+    snapshot.version = SNAPSHOT_VERSION + 1;
+
+    match Game::restore(snapshot) {
+        Err(SnapshotError::UnsupportedVersion { version, supported }) => {
+            assert_eq!(version, SNAPSHOT_VERSION + 1);
+            assert_eq!(supported, SNAPSHOT_VERSION);
+        }
+        Ok(_) => panic!("Restored a snapshot with an unsupported version"),
+    }
+}
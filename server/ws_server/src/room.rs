@@ -21,34 +21,53 @@ pub enum RoomEvent {
         player_id: usize,
         username: String,
     },
+    /// Sent when a player in the lobby picks a team for fixed partnerships
+    TeamChosen {
+        player_id: usize,
+        team: usize,
+    },
     GameStarted {
         player_order: Vec<usize>,
         game_settings: GameSettings,
+        /// The session id of the dealer, the player after them bids first
+        dealer: usize,
     },
     PlayerHand {
         hand: Deck,
     },
     PlayerBid {
         player_id: usize,
-        bid: Option<u32>,
+        bid: Option<Bid>,
     },
     NextBidder {
         player_id: usize,
     },
     BiddingOver {
         napoleon_id: usize,
-        bid: u32,
+        bid: Bid,
     },
     NoBids,
+    /// Sent to the napoleon with the cards from the kitty that have been added to their hand
+    Kitty {
+        cards: Deck,
+    },
+    KittyDiscarded,
     BecomeAlly,
+    /// Sent to the napoleon when nobody else holds any of the cards they called
+    GoingSolo,
     AlliesChosen {
         allies: Vec<Card>,
-        trump_suit: Suit,
+        trump: Trump,
     },
     CardPlayed {
         player_id: usize,
         card: Card,
     },
+    /// Sent after the card that revealed the ally has been played
+    AllyRevealed {
+        player_id: usize,
+        card: Card,
+    },
     NextPlayer {
         player_id: usize,
         required_suit: Option<Suit>,
@@ -56,13 +75,52 @@ pub enum RoomEvent {
     RoundOver {
         winner: usize,
     },
+    ClaimMade {
+        player_id: usize,
+        kind: ClaimKind,
+        /// Whether the claim is certain to come true from the cards in everyone's hands, `None` if
+        /// there were too many cards left to check
+        certain: Option<bool>,
+    },
+    ClaimAccepted {
+        player_id: usize,
+    },
+    ClaimRejected {
+        player_id: usize,
+    },
     GameOver {
         allies: Vec<usize>,
         napoleon_score_delta: i32,
         player_score_delta: i32,
-        napoleon_bet: u32,
+        napoleon_bet: Bid,
         combined_napoleon_score: u32,
+        /// The total score of every player across all the games played in the room, highest first
+        standings: Vec<(usize, i32)>,
+    },
+    /// Sent to a session when the game rejects something that they sent
+    Error {
+        code: &'static str,
+        message: String,
+    },
+    /// Sent when the host sets up a match, the scores are reset
+    MatchStarted {
+        target_score: Option<i32>,
+        deals: Option<u32>,
     },
+    /// Sent after the game which reached the match's target score or number of deals
+    MatchOver {
+        /// The final score of every player, highest first
+        rankings: Vec<(usize, i32)>,
+    },
+}
+
+/// The goal of a match, the match is over as soon as either of these is reached
+#[derive(Clone)]
+pub struct MatchSettings {
+    /// The match ends when a player's total score reaches this
+    pub target_score: Option<i32>,
+    /// The match ends after this many games have been played
+    pub deals: Option<u32>,
 }
 
 pub enum RoomState {
@@ -70,7 +128,7 @@ pub enum RoomState {
     /// id_map: Index of the vector is the player id from the game, and the value is the session_id
     /// of the player
     InGame {
-        game: Game,
+        game: Box<Game>,
         id_map: Vec<usize>,
     },
 }
@@ -82,8 +140,21 @@ struct Occupant {
 
 pub struct Room {
     players: HashMap<usize, Occupant>,
+    /// Every session in the order they joined, which is the order they sit around the table
+    seats: Vec<usize>,
     state: RoomState,
     host: usize,
+    /// The seat (index into the id_map) that deals the next game, this moves one seat along after
+    /// every game (including redeals) so that nobody is always first to bid
+    next_dealer: usize,
+    /// The team that each session picked in the lobby, only used for fixed partnerships
+    teams: HashMap<usize, usize>,
+    /// The running total score of each session across every game played in the room
+    scores: HashMap<usize, i32>,
+    /// The match being played, if any
+    match_settings: Option<MatchSettings>,
+    /// The number of games finished in the current match
+    deals_played: u32,
     logger: Logger,
 }
 
@@ -105,8 +176,14 @@ impl Room {
 
         Room {
             players,
+            seats: vec![session_id],
             state: RoomState::Lobby {},
             host: session_id,
+            next_dealer: 0,
+            teams: HashMap::new(),
+            scores: HashMap::new(),
+            match_settings: None,
+            deals_played: 0,
             logger,
         }
     }
@@ -123,6 +200,17 @@ impl Room {
         }
     }
 
+    /// Tells the session why the game rejected what they sent
+    fn send_error(&self, session_id: usize, error: game::Error) {
+        self.send_event(
+            &session_id,
+            RoomEvent::Error {
+                code: error.code(),
+                message: error.to_string(),
+            },
+        );
+    }
+
     fn broadcast(&self, event: RoomEvent) {
         for occupant in self.players.values() {
             Self::send_recipient_event(&occupant.recipient, event.clone());
@@ -148,27 +236,145 @@ impl Room {
                 recipient: session.clone(),
             },
         );
+        if !self.seats.contains(&session_id) {
+            self.seats.push(session_id);
+        }
         let _ = session.do_send(super::RoomEvent::JoinedRoom {
             address: room_addr,
             key: room_key,
             host: self.host,
             players: self
-                .players
+                .seats
                 .iter()
-                .map(|(session_id, occ)| (occ.username.clone(), *session_id))
+                .map(|session_id| (self.players[session_id].username.clone(), *session_id))
                 .collect(),
         });
     }
 
-    fn start_game(&mut self, session_id: usize, settings: GameSettings) {
+    fn choose_team(&mut self, session_id: usize, team: usize) {
+        if let RoomState::Lobby {} = &self.state {
+            self.teams.insert(session_id, team);
+            self.broadcast(RoomEvent::TeamChosen {
+                player_id: session_id,
+                team,
+            });
+        } else {
+            warn!(
+                self.logger,
+                "Session tried to choose a team when the room state wasn't lobby";
+                "session_id" => session_id
+            );
+        }
+    }
+
+    fn start_match(&mut self, session_id: usize, match_settings: MatchSettings) {
+        if let RoomState::Lobby {} = &self.state {
+            if session_id != self.host {
+                warn!(self.logger, "Non-host tried to start a match"; "session_id" => session_id, "host_id" => self.host);
+                return;
+            }
+
+            self.scores.clear();
+            self.deals_played = 0;
+            self.broadcast(RoomEvent::MatchStarted {
+                target_score: match_settings.target_score,
+                deals: match_settings.deals,
+            });
+            self.match_settings = Some(match_settings);
+        } else {
+            warn!(
+                self.logger,
+                "Session tried to start a match when the room state wasn't lobby";
+                "session_id" => session_id
+            );
+        }
+    }
+
+    /// Called after every finished game, ends the match if it has reached its target.
+    fn end_deal(&mut self, standings: Vec<(usize, i32)>) {
+        let match_settings = if let Some(match_settings) = &self.match_settings {
+            match_settings
+        } else {
+            return;
+        };
+
+        self.deals_played += 1;
+        let target_reached = match match_settings.target_score {
+            Some(target_score) => standings.iter().any(|(_, score)| *score >= target_score),
+            None => false,
+        };
+        let deals_reached = match match_settings.deals {
+            Some(deals) => self.deals_played >= deals,
+            None => false,
+        };
+
+        if target_reached || deals_reached {
+            self.match_settings = None;
+            self.broadcast(RoomEvent::MatchOver {
+                rankings: standings,
+            });
+        }
+    }
+
+    /// Seats the players so that the teams alternate around the table, e.g. with two teams of two
+    /// the partners sit opposite each other. Returns the id_map and the team of each seat.
+    fn seat_teams(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut members: Vec<(usize, Vec<usize>)> = Vec::new();
+        for session_id in &self.seats {
+            let team = self.teams[session_id];
+            match members.iter_mut().find(|(t, _)| *t == team) {
+                Some((_, sessions)) => sessions.push(*session_id),
+                None => members.push((team, vec![*session_id])),
+            }
+        }
+        members.sort_by_key(|(team, _)| *team);
+
+        let mut id_map = Vec::new();
+        let mut teams = Vec::new();
+        let most = members.iter().map(|(_, s)| s.len()).max().unwrap_or(0);
+        for i in 0..most {
+            for (team, sessions) in &members {
+                if let Some(session_id) = sessions.get(i) {
+                    id_map.push(*session_id);
+                    teams.push(*team);
+                }
+            }
+        }
+
+        (id_map, teams)
+    }
+
+    fn start_game(&mut self, session_id: usize, settings: GameSettingsBuilder) {
         if let RoomState::Lobby {} = &self.state {
             if session_id != self.host {
                 warn!(self.logger, "Non-host tried to start game"; "session_id" => session_id, "host_id" => self.host);
                 return;
             }
-            let id_map = self.players.keys().cloned().collect();
 
-            self.new_game(settings, id_map);
+            let chosen = self
+                .players
+                .keys()
+                .filter(|id| self.teams.contains_key(id))
+                .count();
+            let (id_map, settings) = if chosen == 0 {
+                (self.seats.clone(), settings.teams(None))
+            } else if chosen == self.players.len() {
+                let (id_map, teams) = self.seat_teams();
+                (id_map, settings.teams(Some(teams)))
+            } else {
+                warn!(self.logger, "Tried to start a game where only some players chose a team"; "session_id" => session_id);
+                return;
+            };
+
+            // The settings are checked here so that bad settings from the host can't make dealing
+            // panic
+            match settings.build(id_map.len()) {
+                Ok(settings) => self.new_game(settings, id_map),
+                Err(error) => {
+                    warn!(self.logger, "Host tried to start a game with invalid settings"; "session_id" => session_id, "error" => error.to_string());
+                    self.send_error(session_id, error.into());
+                }
+            }
         } else {
             warn!(
                 self.logger,
@@ -184,26 +390,38 @@ impl Room {
             return;
         }
 
-        let game = Game::new(id_map.len(), game_settings.clone());
+        let dealer = self.next_dealer % id_map.len();
+        self.next_dealer = dealer + 1;
+
+        let game = Game::with_dealer(id_map.len(), game_settings.clone(), rand::random(), dealer);
         self.broadcast(RoomEvent::GameStarted {
             player_order: id_map.clone(),
             game_settings,
+            dealer: id_map[dealer],
         });
 
-        for (hand, session_id) in game.get_hands().iter().zip(id_map.iter()) {
-            self.send_event(session_id, RoomEvent::PlayerHand { hand: hand.clone() });
+        for (player_id, session_id) in id_map.iter().enumerate() {
+            self.send_event(
+                session_id,
+                RoomEvent::PlayerHand {
+                    hand: game.view_for(player_id).hand,
+                },
+            );
         }
 
         self.broadcast(RoomEvent::NextBidder {
-            player_id: id_map[0],
+            player_id: id_map[game.first_bidder()],
         });
 
-        trace!(self.logger, "New game started"; "players" => id_map.len());
+        trace!(self.logger, "New game started"; "players" => id_map.len(), "seed" => game.get_seed(), "dealer" => dealer, "deal" => game.get_deal().to_string());
 
-        self.state = RoomState::InGame { game, id_map };
+        self.state = RoomState::InGame {
+            game: Box::new(game),
+            id_map,
+        };
     }
 
-    fn bid(&mut self, session_id: usize, bid: Option<u32>) {
+    fn bid(&mut self, session_id: usize, bid: Option<Bid>) {
         use BiddingError::*;
         use BiddingEvent::*;
 
@@ -213,8 +431,11 @@ impl Room {
         } = self.state
         {
             if let Some(player_id) = id_map.iter().position(|id| session_id == *id) {
-                match game.bid(player_id, bid) {
+                match game.bid(player_id, bid.clone()) {
                     Ok(event) => {
+                        // Broadcast the bid as the game recorded it, e.g. bidding every round is
+                        // a nap
+                        let bid = game.get_bids().last().and_then(|(_, bid)| bid.clone());
                         self.broadcast(RoomEvent::PlayerBid {
                             player_id: session_id,
                             bid,
@@ -225,49 +446,66 @@ impl Room {
                                     player_id: id_map[player_id],
                                 });
                             }
-                            BiddingFinished { napoleon } => {
+                            BiddingFinished { napoleon, kitty } => {
                                 let napoleon_id = id_map[napoleon.player_id];
                                 self.broadcast(RoomEvent::BiddingOver {
                                     bid: napoleon.bid,
                                     napoleon_id,
                                 });
+
+                                if !kitty.is_empty() {
+                                    self.send_event(
+                                        &napoleon_id,
+                                        RoomEvent::Kitty { cards: kitty },
+                                    );
+                                }
                             }
                         }
                     }
-                    Err(error) => match error {
-                        InvalidGameState => warn!(
-                            self.logger,
-                            "Session tried to bid when game state wasn't bidding";
-                            "session_id" => session_id
-                        ),
-                        BidTooLow { min } => warn!(
-                            self.logger,
-                            "Session tried to bid below the minimum";
-                            "session_id" => session_id,
-                            "minimum" => min,
-                            "bid" => bid
-                        ),
-                        BidTooHigh { max } => warn!(
-                            self.logger,
-                            "Session tried to bid above the maximum";
-                            "session_id" => session_id,
-                            "maximum" => max,
-                            "bid" => bid
-                        ),
-                        NoBids => {
-                            trace!(self.logger, "No bids so starting a new game");
-                            let settings = game.get_settings().clone();
-                            let id_map = id_map.clone();
-                            self.broadcast(RoomEvent::NoBids);
-                            self.new_game(settings, id_map);
+                    Err(NoBids) => {
+                        trace!(self.logger, "No bids so starting a new game");
+                        let settings = game.get_settings().clone();
+                        let id_map = id_map.clone();
+                        self.broadcast(RoomEvent::NoBids);
+                        self.new_game(settings, id_map);
+                    }
+                    Err(error) => {
+                        self.send_error(session_id, error.clone().into());
+                        match error {
+                            InvalidGameState => warn!(
+                                self.logger,
+                                "Session tried to bid when game state wasn't bidding";
+                                "session_id" => session_id
+                            ),
+                            BidTooLow { min } => warn!(
+                                self.logger,
+                                "Session tried to bid below the minimum";
+                                "session_id" => session_id,
+                                "minimum" => min.to_string(),
+                                "bid" => bid.map(|bid| bid.to_string())
+                            ),
+                            BidTooHigh { max } => warn!(
+                                self.logger,
+                                "Session tried to bid above the maximum";
+                                "session_id" => session_id,
+                                "maximum" => max,
+                                "bid" => bid.map(|bid| bid.to_string())
+                            ),
+                            BidNotAllowed => warn!(
+                                self.logger,
+                                "Session made a bid that isn't allowed over the current bid";
+                                "session_id" => session_id,
+                                "bid" => bid.map(|bid| bid.to_string())
+                            ),
+                            NoBids => {}
+                            NotCurrentPlayer { current_player } => warn!(
+                                self.logger,
+                                "Session tried to bid when they weren't the current bidder";
+                                "session_id" => session_id,
+                                "current_bidder" => current_player
+                            ),
                         }
-                        NotCurrentPlayer { current_player } => warn!(
-                            self.logger,
-                            "Session tried to bid when they weren't the current bidder";
-                            "session_id" => session_id,
-                            "current_bidder" => current_player
-                        ),
-                    },
+                    }
                 }
             } else {
                 warn!(
@@ -285,7 +523,66 @@ impl Room {
         }
     }
 
-    fn pick_allies(&mut self, session_id: usize, ally_cards: Vec<Card>, trump_suit: Suit) {
+    fn discard(&mut self, session_id: usize, cards: Vec<Card>) {
+        use ExchangeError::*;
+        use ExchangeEvent::*;
+
+        if let RoomState::InGame {
+            ref mut game,
+            ref id_map,
+        } = self.state
+        {
+            if let Some(player_id) = id_map.iter().position(|id| session_id == *id) {
+                match game.discard(player_id, cards) {
+                    Ok(event) => match event {
+                        KittyDiscarded => self.broadcast(RoomEvent::KittyDiscarded),
+                    },
+                    Err(error) => {
+                        self.send_error(session_id, error.clone().into());
+                        match error {
+                            NotCurrentPlayer { current_player } => warn!(
+                                self.logger,
+                                "Session tried to discard when they weren't the napoleon";
+                                "session_id" => session_id,
+                                "napoleon" => current_player,
+                            ),
+                            InvalidGameState => warn!(
+                                self.logger,
+                                "Session tried to discard when game state wasn't exchanging";
+                                "session_id" => session_id
+                            ),
+                            IncorrectDiscardCount { expected, received } => warn!(
+                                self.logger,
+                                "Session discarded an incorrect number of cards";
+                                "session_id" => session_id,
+                                "expected" => expected,
+                                "received" => received,
+                            ),
+                            CardNotInHand => warn!(
+                                self.logger,
+                                "Session tried to discard a card that they didn't have";
+                                "session_id" => session_id,
+                            ),
+                        }
+                    }
+                }
+            } else {
+                warn!(
+                    self.logger,
+                    "Non player tried to discard (was spectator)";
+                    "session_id" => session_id
+                );
+            }
+        } else {
+            warn!(
+                self.logger,
+                "Session tried to discard when the room state wasn't in game";
+                "session_id" => session_id
+            );
+        }
+    }
+
+    fn pick_allies(&mut self, session_id: usize, ally_cards: Vec<Card>, trump: Trump) {
         use PostBiddingError::*;
         use PostBiddingEvent::*;
 
@@ -295,45 +592,81 @@ impl Room {
         } = self.state
         {
             if let Some(player_id) = id_map.iter().position(|id| session_id == *id) {
-                match game.pick_allies(player_id, ally_cards.clone(), trump_suit.clone()) {
+                match game.pick_allies(player_id, ally_cards.clone(), trump.clone()) {
                     Ok(event) => match event {
-                        AlliesChosen { allies } => {
+                        AlliesChosen { allies, solo } => {
+                            let required_suit =
+                                game.get_settings().rules.first_required_suit(&trump);
+
                             self.broadcast(RoomEvent::AlliesChosen {
                                 allies: ally_cards,
-                                trump_suit: trump_suit.clone(),
+                                trump: trump.clone(),
                             });
 
                             for ally in allies {
                                 self.send_event(&id_map[ally], RoomEvent::BecomeAlly);
                             }
+                            // Only the napoleon finds out, everyone else has to work it out from
+                            // the cards that are played
+                            if solo {
+                                self.send_event(&session_id, RoomEvent::GoingSolo);
+                            }
 
                             // Session_id here must be napoleon
                             self.broadcast(RoomEvent::NextPlayer {
                                 player_id: session_id,
-                                required_suit: Some(trump_suit),
+                                required_suit,
                             });
                         }
                     },
-                    Err(error) => match error {
-                        NotCurrentPlayer { current_player } => warn!(
-                            self.logger,
-                            "Session tried to pick allies when they weren't the napoleon";
-                            "session_id" => session_id,
-                            "napoleon" => current_player,
-                        ),
-                        InvalidGameState => warn!(
-                            self.logger,
-                            "Session tried to pick allies when game state wasn't pick_allies";
-                            "session_id" => session_id
-                        ),
-                        IncorrectAllyCount { expected, received } => warn!(
-                            self.logger,
-                            "Session picked an incorrect number of allies";
-                            "session_id" => session_id,
-                            "expected" => expected,
-                            "received" => received,
-                        ),
-                    },
+                    Err(error) => {
+                        self.send_error(session_id, error.clone().into());
+                        match error {
+                            NotCurrentPlayer { current_player } => warn!(
+                                self.logger,
+                                "Session tried to pick allies when they weren't the napoleon";
+                                "session_id" => session_id,
+                                "napoleon" => current_player,
+                            ),
+                            InvalidGameState => warn!(
+                                self.logger,
+                                "Session tried to pick allies when game state wasn't pick_allies";
+                                "session_id" => session_id
+                            ),
+                            IncorrectAllyCount { expected, received } => warn!(
+                                self.logger,
+                                "Session picked an incorrect number of allies";
+                                "session_id" => session_id,
+                                "expected" => expected,
+                                "received" => received,
+                            ),
+                            DuplicateAllyCard => warn!(
+                                self.logger,
+                                "Session picked the same ally card twice";
+                                "session_id" => session_id,
+                            ),
+                            InvalidTrumpSuit => warn!(
+                                self.logger,
+                                "Session tried to make jokers trumps";
+                                "session_id" => session_id,
+                            ),
+                            NoTrumpBid => warn!(
+                                self.logger,
+                                "Session picked a trump suit after bidding no trumps";
+                                "session_id" => session_id,
+                            ),
+                            NoTrumpNotBid => warn!(
+                                self.logger,
+                                "Session picked no trumps without bidding no trumps";
+                                "session_id" => session_id,
+                            ),
+                            CalledOwnCard => warn!(
+                                self.logger,
+                                "Session called a card in their own hand when it wasn't allowed";
+                                "session_id" => session_id,
+                            ),
+                        }
+                    }
                 }
             } else {
                 warn!(
@@ -362,97 +695,244 @@ impl Room {
         {
             if let Some(player_id) = id_map.iter().position(|id| session_id == *id) {
                 match game.play_card(player_id, card.clone()) {
-                    Ok(event) => {
+                    Ok(events) => {
                         self.broadcast(RoomEvent::CardPlayed {
                             player_id: session_id,
                             card,
                         });
-                        match event {
-                            NextPlayer {
-                                player_id,
-                                required_suit,
-                            } => {
-                                self.broadcast(RoomEvent::NextPlayer {
-                                    player_id: id_map[player_id],
-                                    required_suit: Some(required_suit),
-                                });
-                            }
-                            RoundEnded {
-                                winner,
-                                next_player,
-                            } => {
-                                self.broadcast(RoomEvent::RoundOver {
-                                    winner: id_map[winner],
-                                });
+                        for event in events {
+                            match event {
+                                AllyRevealed { player_id, card } => {
+                                    self.broadcast(RoomEvent::AllyRevealed {
+                                        player_id: id_map[player_id],
+                                        card,
+                                    });
+                                }
+                                NextPlayer {
+                                    player_id,
+                                    required_suit,
+                                } => {
+                                    self.broadcast(RoomEvent::NextPlayer {
+                                        player_id: id_map[player_id],
+                                        required_suit,
+                                    });
+                                }
+                                RoundEnded {
+                                    winner,
+                                    next_player,
+                                    ..
+                                } => {
+                                    self.broadcast(RoomEvent::RoundOver {
+                                        winner: id_map[winner],
+                                    });
 
-                                self.broadcast(RoomEvent::NextPlayer {
-                                    player_id: id_map[next_player],
-                                    required_suit: None,
-                                });
-                            }
-                            GameEnded {
-                                combined_napoleon_score,
-                                napoleon,
-                                allies,
-                            } => {
-                                // TODO: decide scoring
-                                // TODO: implement room wide score
-                                let (napoleon_score_delta, player_score_delta) =
-                                    if napoleon.bid == combined_napoleon_score {
-                                        (15, -10)
-                                    } else {
-                                        (-10, 15)
-                                    };
-
-                                self.broadcast(RoomEvent::GameOver {
-                                    napoleon_score_delta,
-                                    player_score_delta,
-                                    allies,
-                                    combined_napoleon_score,
-                                    napoleon_bet: napoleon.bid,
-                                });
+                                    self.broadcast(RoomEvent::NextPlayer {
+                                        player_id: id_map[next_player],
+                                        required_suit: None,
+                                    });
+                                }
+                                event @ GameEnded { .. } => {
+                                    self.game_over(event);
+                                    break;
+                                }
                             }
                         }
                     }
-                    Err(error) => match error {
-                        InvalidGameState => warn!(
-                            self.logger,
-                            "Session tried to play card when game state wasn't in round";
-                            "session_id" => session_id
-                        ),
-                        NotCurrentPlayer { current_player } => warn!(
-                            self.logger,
-                            "Session tried to play card when they weren't the current player";
-                            "session_id" => session_id,
-                            "current_bidder" => current_player
-                        ),
-                        CardNotInHand => warn!(
+                    Err(error) => {
+                        self.send_error(session_id, error.clone().into());
+                        match error {
+                            InvalidGameState => warn!(
+                                self.logger,
+                                "Session tried to play card when game state wasn't in round";
+                                "session_id" => session_id
+                            ),
+                            NotCurrentPlayer { current_player } => warn!(
+                                self.logger,
+                                "Session tried to play card when they weren't the current player";
+                                "session_id" => session_id,
+                                "current_bidder" => current_player
+                            ),
+                            CardNotInHand => warn!(
+                                self.logger,
+                                "Session tried to play a card that they didn't have";
+                                "session_id" => session_id,
+                            ),
+                            InvalidSuit => warn!(
+                                self.logger,
+                                "Session tried to play a card of the wrong suit";
+                                "session_id" => session_id,
+                            ),
+                            ClaimPending => warn!(
+                                self.logger,
+                                "Session tried to play a card before the claim was answered";
+                                "session_id" => session_id,
+                            ),
+                        }
+                    }
+                }
+            } else {
+                warn!(
+                    self.logger,
+                    "Non player tried to pick allies (was spectator)";
+                    "session_id" => session_id
+                );
+            }
+        } else {
+            warn!(
+                self.logger,
+                "Session tried to play card when the room state wasn't in game";
+                "session_id" => session_id
+            );
+        }
+    }
+
+    fn claim(&mut self, session_id: usize, kind: ClaimKind) {
+        if let RoomState::InGame {
+            ref mut game,
+            ref id_map,
+        } = self.state
+        {
+            if let Some(player_id) = id_map.iter().position(|id| session_id == *id) {
+                match game.claim(player_id, kind.clone()) {
+                    Ok(_) => {
+                        let certain = game.check_claim(player_id, &kind);
+                        self.broadcast(RoomEvent::ClaimMade {
+                            player_id: session_id,
+                            kind,
+                            certain,
+                        });
+                    }
+                    Err(error) => {
+                        warn!(
                             self.logger,
-                            "Session tried to play a card that they didn't have";
+                            "Session couldn't make a claim";
                             "session_id" => session_id,
-                        ),
-                        InvalidSuit => warn!(
+                            "error" => error.to_string(),
+                        );
+                        self.send_error(session_id, error.into());
+                    }
+                }
+            } else {
+                warn!(
+                    self.logger,
+                    "Non player tried to make a claim (was spectator)";
+                    "session_id" => session_id
+                );
+            }
+        } else {
+            warn!(
+                self.logger,
+                "Session tried to make a claim when the room state wasn't in game";
+                "session_id" => session_id
+            );
+        }
+    }
+
+    fn respond_to_claim(&mut self, session_id: usize, accept: bool) {
+        if let RoomState::InGame {
+            ref mut game,
+            ref id_map,
+        } = self.state
+        {
+            if let Some(player_id) = id_map.iter().position(|id| session_id == *id) {
+                match game.respond_to_claim(player_id, accept) {
+                    Ok(ClaimEvent::AwaitingResponses { .. }) => {
+                        self.broadcast(RoomEvent::ClaimAccepted {
+                            player_id: session_id,
+                        });
+                    }
+                    Ok(ClaimEvent::Rejected { .. }) => {
+                        self.broadcast(RoomEvent::ClaimRejected {
+                            player_id: session_id,
+                        });
+                    }
+                    Ok(ClaimEvent::Settled(event)) => {
+                        self.broadcast(RoomEvent::ClaimAccepted {
+                            player_id: session_id,
+                        });
+                        self.game_over(event);
+                    }
+                    Err(error) => {
+                        warn!(
                             self.logger,
-                            "Session tried to play a card of the wrong suit";
+                            "Session couldn't respond to the claim";
                             "session_id" => session_id,
-                        ),
-                    },
+                            "error" => error.to_string(),
+                        );
+                        self.send_error(session_id, error.into());
+                    }
                 }
             } else {
                 warn!(
                     self.logger,
-                    "Non player tried to pick allies (was spectator)";
+                    "Non player tried to respond to a claim (was spectator)";
                     "session_id" => session_id
                 );
             }
         } else {
             warn!(
                 self.logger,
-                "Session tried to play card when the room state wasn't in game";
+                "Session tried to respond to a claim when the room state wasn't in game";
                 "session_id" => session_id
             );
         }
     }
+
+    /// Scores a finished game, sends everyone the result and goes back to the lobby so that the
+    /// host can start the next game.
+    fn game_over(&mut self, event: PlayingEvent) {
+        let id_map = if let RoomState::InGame {
+            ref game,
+            ref id_map,
+        } = self.state
+        {
+            debug!(
+                self.logger,
+                "Game finished";
+                "seed" => game.get_seed(),
+                "log" => serde_json::to_string(game.get_log()).expect("Serialization failed"),
+            );
+            id_map.clone()
+        } else {
+            return;
+        };
+
+        if let PlayingEvent::GameEnded {
+            combined_napoleon_score,
+            napoleon,
+            allies,
+            score,
+            ..
+        } = event
+        {
+            let deltas = score.deltas(id_map.len(), napoleon.player_id, &allies);
+            for (player_id, delta) in deltas.into_iter().enumerate() {
+                let total = self.scores.entry(id_map[player_id]).or_insert(0);
+                *total = total.saturating_add(delta);
+            }
+
+            let mut standings: Vec<(usize, i32)> = self
+                .scores
+                .iter()
+                .map(|(session_id, score)| (*session_id, *score))
+                .collect();
+            standings.sort_by_key(|(session_id, score)| (-score, *session_id));
+
+            let allies = allies.iter().map(|ally| id_map[*ally]).collect();
+            self.broadcast(RoomEvent::GameOver {
+                napoleon_score_delta: score.napoleon_side,
+                player_score_delta: score.opponents,
+                allies,
+                combined_napoleon_score,
+                napoleon_bet: napoleon.bid,
+                standings: standings.clone(),
+            });
+
+            // Go back to the lobby so that the host can start the next game
+            self.state = RoomState::Lobby {};
+            self.end_deal(standings);
+        }
+    }
 }
 
 impl Actor for Room {
@@ -1,4 +1,4 @@
-use super::Room;
+use super::{MatchSettings, Room};
 use crate::{game_server::PlayerJoined, WebsocketMessage};
 use actix::prelude::*;
 
@@ -20,20 +20,30 @@ impl Room {
         };
 
         match first_char {
-            's' => self.start_game(
-                session_id,
-                game::GameSettings {
-                    ally_count: 1,
-                    hand_size: 5,
-                },
-            ),
+            's' => {
+                let settings = if content.len() > 1 {
+                    match serde_json::from_str(&content[1..]) {
+                        Ok(settings) => settings,
+                        Err(error) => {
+                            warn!(self.logger, "Couldn't parse game settings from session"; "session_id" => session_id, "error" => error.to_string());
+                            return;
+                        }
+                    }
+                } else {
+                    GameSettings::builder()
+                };
+
+                self.start_game(session_id, settings);
+            }
             'b' => {
-                let bid: Option<u32> = if content.len() > 1 {
-                    if let Ok(bid) = content[1..].parse() {
-                        Some(bid)
-                    } else {
-                        warn!(self.logger, "Couldn't parse bid from session"; "session_id" => session_id, "bid" => &content[1..]);
-                        return;
+                let bid: Option<Bid> = if content.len() > 1 {
+                    match content[1..].parse() {
+                        Ok(bid) => Some(bid),
+                        Err(error) => {
+                            warn!(self.logger, "Couldn't parse bid from session"; "session_id" => session_id, "bid" => &content[1..]);
+                            self.send_error(session_id, Error::ParseBid(error));
+                            return;
+                        }
                     }
                 } else {
                     None
@@ -42,57 +52,109 @@ impl Room {
                 self.bid(session_id, bid);
             }
             'a' => {
-                let trump_suit = if let Some(c) = message.next() {
-                    if let Ok(suit) = Suit::from_char(c) {
-                        suit
-                    } else {
-                        warn!(self.logger, "Invalid suit character for trump suit"; "session_id" => session_id, "suit_char" => c);
+                let mut parts = content[1..].splitn(2, ',');
+
+                let trump = match parts.next().unwrap_or("").parse::<Trump>() {
+                    Ok(trump) => trump,
+                    Err(error) => {
+                        warn!(self.logger, "Couldn't parse trump in pick allies"; "session_id" => session_id, "error" => error.to_string());
+                        self.send_error(session_id, error.into());
                         return;
                     }
-                } else {
-                    warn!(self.logger, "Pick allies message had no trump suit"; "session_id" => session_id);
-                    return;
                 };
 
-                let mut ally_cards = Vec::new();
+                let ally_cards = match parts.next().unwrap_or("").parse::<Deck>() {
+                    Ok(cards) => cards.into_iter().collect(),
+                    Err(error) => {
+                        warn!(self.logger, "Couldn't parse cards in pick allies"; "session_id" => session_id, "error" => error.to_string());
+                        self.send_error(session_id, error.into());
+                        return;
+                    }
+                };
 
-                while let (Some(','), Some(n), Some(s)) =
-                    (message.next(), message.next(), message.next())
-                {
-                    let card = if let Ok(card) = Card::from_chars(s, n) {
-                        card
-                    } else {
-                        warn!(self.logger, "Couldn't parse card from chars in pick allies"; "session_id" => session_id, "suit_char" => s, "number_char" => n);
+                self.pick_allies(session_id, ally_cards, trump);
+            }
+            'd' => {
+                let cards = match content[1..].parse::<Deck>() {
+                    Ok(cards) => cards.into_iter().collect(),
+                    Err(error) => {
+                        warn!(self.logger, "Couldn't parse cards in discard"; "session_id" => session_id, "error" => error.to_string());
+                        self.send_error(session_id, error.into());
                         return;
-                    };
-                    ally_cards.push(card);
-                }
+                    }
+                };
 
-                self.pick_allies(session_id, ally_cards, trump_suit);
+                self.discard(session_id, cards);
             }
-            'p' => {
-                let n = if let Some(n) = message.next() {
-                    n
-                } else {
-                    warn!(self.logger, "No number in play card"; "session_id" => session_id);
-                    return;
+            'm' => {
+                let mut goals = content[1..].splitn(2, ',');
+
+                let target_score = match goals.next().unwrap_or("") {
+                    "" => None,
+                    goal => match goal.parse() {
+                        Ok(target_score) => Some(target_score),
+                        Err(_) => {
+                            warn!(self.logger, "Couldn't parse target score from session"; "session_id" => session_id, "target_score" => goal);
+                            return;
+                        }
+                    },
                 };
 
-                let s = if let Some(s) = message.next() {
-                    s
-                } else {
-                    warn!(self.logger, "No suit in play card"; "session_id" => session_id);
-                    return;
+                let deals = match goals.next().unwrap_or("") {
+                    "" => None,
+                    goal => match goal.parse() {
+                        Ok(deals) => Some(deals),
+                        Err(_) => {
+                            warn!(self.logger, "Couldn't parse number of deals from session"; "session_id" => session_id, "deals" => goal);
+                            return;
+                        }
+                    },
                 };
 
-                let card = if let Ok(card) = Card::from_chars(s, n) {
-                    card
+                if target_score.is_none() && deals.is_none() {
+                    warn!(self.logger, "Match had neither a target score nor a number of deals"; "session_id" => session_id);
+                    return;
+                }
+
+                self.start_match(
+                    session_id,
+                    MatchSettings {
+                        target_score,
+                        deals,
+                    },
+                );
+            }
+            't' => {
+                let team = if let Ok(team) = content[1..].parse() {
+                    team
                 } else {
-                    warn!(self.logger, "Couldn't parse card from chars in play card"; "session_id" => session_id, "suit_char" => s, "number_char" => n);
+                    warn!(self.logger, "Couldn't parse team from session"; "session_id" => session_id, "team" => &content[1..]);
                     return;
                 };
+
+                self.choose_team(session_id, team);
+            }
+            'p' => {
+                let card = match content[1..].parse::<Card>() {
+                    Ok(card) => card,
+                    Err(error) => {
+                        warn!(self.logger, "Couldn't parse card in play card"; "session_id" => session_id, "error" => error.to_string());
+                        self.send_error(session_id, error.into());
+                        return;
+                    }
+                };
+
                 self.play_card(session_id, card);
             }
+            'w' => self.claim(session_id, ClaimKind::AllTricks),
+            'q' => self.claim(session_id, ClaimKind::Concede),
+            'r' => match &content[1..] {
+                "y" => self.respond_to_claim(session_id, true),
+                "n" => self.respond_to_claim(session_id, false),
+                response => {
+                    warn!(self.logger, "Couldn't parse claim response from session"; "session_id" => session_id, "response" => response);
+                }
+            },
             _ => {}
         }
     }
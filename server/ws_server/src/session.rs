@@ -143,25 +143,45 @@ impl Handler<RoomEvent> for Session {
                 player_id,
                 username,
             } => format!("j{},{}", username, player_id),
+            E::MatchStarted {
+                target_score,
+                deals,
+            } => format!(
+                "m{},{}",
+                target_score.map(|t| t.to_string()).unwrap_or_default(),
+                deals.map(|d| d.to_string()).unwrap_or_default()
+            ),
+            E::MatchOver { rankings } => format!(
+                "mo{}",
+                rankings
+                    .into_iter()
+                    .map(|(player_id, score)| format!("{}:{}", player_id, score))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            E::Error { code, message } => format!("x{},{}", code, message),
+            E::TeamChosen { player_id, team } => format!("t{},{}", player_id, team),
             E::NoBids => format!("nb"),
             E::BiddingOver { bid, napoleon_id } => format!("bo{},{}", bid, napoleon_id),
-            E::AlliesChosen { allies, trump_suit } => {
+            E::AlliesChosen { allies, trump } => {
                 let mut output = String::from("ac");
-                output.push(trump_suit.to_char());
+                output.push_str(&trump.to_string());
                 for ally in allies {
-                    output.push(',');
-                    output.push_str(&format!("{},{}", ally.number, ally.suit.to_char()));
+                    output.push_str(&format!(",{}", ally));
                 }
 
                 output
             }
+            E::Kitty { cards } => format!("k{}", cards),
+            E::KittyDiscarded => "kd".to_string(),
             E::BecomeAlly => format!("ab"),
+            E::GoingSolo => "as".to_string(),
             E::NextPlayer {
                 player_id,
                 required_suit,
             } => {
                 if let Some(suit) = required_suit {
-                    format!("n{},{}", player_id, suit.to_char())
+                    format!("n{},{}", player_id, suit)
                 } else {
                     format!("n{}", player_id)
                 }
@@ -169,32 +189,49 @@ impl Handler<RoomEvent> for Session {
             E::GameStarted {
                 player_order,
                 game_settings,
+                dealer,
             } => format!(
-                "s{}\n{}",
+                "s{}\n{}\n{}",
                 player_order
                     .into_iter()
                     .map(|id| format!("{}", id))
                     .collect::<Vec<_>>()
                     .join(","),
                 serde_json::to_string(&game_settings).expect("Serialization failed"),
+                dealer,
             ),
-            E::PlayerHand { hand } => format!(
-                "h{}",
-                hand.into_iter()
-                    .map(|card| format!("{}{}", card.number, card.suit.to_char()))
-                    .collect::<Vec<_>>()
-                    .join(",")
-            ),
+            E::PlayerHand { hand } => format!("h{}", hand),
             E::CardPlayed { player_id, card } => {
-                format!("p{},{}{}", player_id, card.number, card.suit.to_char())
+                format!("p{},{}", player_id, card)
+            }
+            E::AllyRevealed { player_id, card } => {
+                format!("ar{},{}", player_id, card)
             }
             E::RoundOver { winner } => format!("r{}", winner),
+            E::ClaimMade {
+                player_id,
+                kind,
+                certain,
+            } => {
+                let claim = match kind {
+                    game::ClaimKind::AllTricks => format!("w{}", player_id),
+                    game::ClaimKind::Concede => format!("q{}", player_id),
+                };
+                match certain {
+                    Some(true) => format!("{},y", claim),
+                    Some(false) => format!("{},n", claim),
+                    None => claim,
+                }
+            }
+            E::ClaimAccepted { player_id } => format!("wa{}", player_id),
+            E::ClaimRejected { player_id } => format!("wr{}", player_id),
             E::GameOver {
                 allies,
                 napoleon_score_delta,
                 player_score_delta,
                 napoleon_bet,
                 combined_napoleon_score,
+                standings,
             } => {
                 let mut s = format!(
                     "g{},{},{},{}",
@@ -203,6 +240,14 @@ impl Handler<RoomEvent> for Session {
                 for ally in allies {
                     s.push_str(&format!(",{}", ally));
                 }
+                s.push('\n');
+                s.push_str(
+                    &standings
+                        .into_iter()
+                        .map(|(player_id, score)| format!("{}:{}", player_id, score))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
                 s
             }
         };